@@ -29,13 +29,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Custom configuration example (uncomment to use):
     // let client = Client::builder()
     //     .proxy("http://127.0.0.1:8080")
-    //     .danger_accept_invalid_certs(false)
     //     .user_agent("guerrillamail-demo/1.0")
     //     .ajax_url("https://www.guerrillamail.com/ajax.php")
     //     .build()
     //     .await?;
 
     println!("   Connected to GuerrillaMail API");
+    println!(
+        "   Available domains: {}",
+        client.domains().await?.join(", ")
+    );
 
     // =========================================
     // 2. Create temporary email address
@@ -138,8 +141,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // =========================================
     println!("\nCleaning up email address...");
     match client.delete_email(&email).await {
-        Ok(true) => println!("   Email address deleted"),
-        Ok(false) => println!("   Deletion may have failed"),
+        Ok(_) => println!("   Email address deleted"),
         Err(e) => eprintln!("   Error: {}", e),
     }
 
@@ -0,0 +1,334 @@
+//! Validated GuerrillaMail addresses.
+
+use crate::{Error, Result};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+use std::str::FromStr;
+
+/// Longest address accepted, per the SMTP path limit (RFC 5321).
+const MAX_ADDRESS_LEN: usize = 254;
+
+/// A syntactically valid `alias@domain` address.
+///
+/// Dereferences to `&str`, so it can be passed to every [`Client`](crate::Client) method that
+/// takes an address. Comparison and hashing ignore ASCII case, like mail servers do.
+///
+/// # Example
+/// ```
+/// use guerrillamail_client::EmailAddress;
+///
+/// let address: EmailAddress = "CI-Run@sharklasers.com".parse()?;
+/// assert_eq!(address.alias(), "CI-Run");
+/// assert_eq!(address.domain(), "sharklasers.com");
+/// assert_eq!(address, "ci-run@SharkLasers.com");
+/// # Ok::<(), guerrillamail_client::Error>(())
+/// ```
+#[derive(Clone)]
+pub struct EmailAddress {
+    address: String,
+    at: usize,
+}
+
+impl EmailAddress {
+    /// Parse and validate an address.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidAddress` unless `address` has exactly one `@`, a non-empty alias, a
+    /// dotted domain, no whitespace or control characters, and at most 254 characters.
+    pub fn parse(address: &str) -> Result<Self> {
+        let invalid = |reason| Error::InvalidAddress {
+            address: address.to_string(),
+            reason,
+        };
+        if address.len() > MAX_ADDRESS_LEN {
+            return Err(invalid("longer than 254 characters"));
+        }
+        if address.chars().any(|c| c.is_whitespace() || c.is_control()) {
+            return Err(invalid("contains whitespace or control characters"));
+        }
+        let Some((alias, domain)) = address.split_once('@') else {
+            return Err(invalid("missing `@`"));
+        };
+        if domain.contains('@') {
+            return Err(invalid("more than one `@`"));
+        }
+        if alias.is_empty() {
+            return Err(invalid("empty alias"));
+        }
+        let labels_ok = domain.split('.').all(|label| !label.is_empty());
+        if !domain.contains('.') || !labels_ok {
+            return Err(invalid("domain is not a dotted host name"));
+        }
+        Ok(Self {
+            address: address.to_string(),
+            at: alias.len(),
+        })
+    }
+
+    /// The local-part before the `@`.
+    pub fn alias(&self) -> &str {
+        &self.address[..self.at]
+    }
+
+    /// The domain after the `@`.
+    pub fn domain(&self) -> &str {
+        &self.address[self.at + 1..]
+    }
+
+    /// The full address, as given.
+    pub fn as_str(&self) -> &str {
+        &self.address
+    }
+}
+
+impl fmt::Debug for EmailAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.address, f)
+    }
+}
+
+impl fmt::Display for EmailAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.address)
+    }
+}
+
+impl FromStr for EmailAddress {
+    type Err = Error;
+
+    fn from_str(address: &str) -> Result<Self> {
+        Self::parse(address)
+    }
+}
+
+impl TryFrom<String> for EmailAddress {
+    type Error = Error;
+
+    fn try_from(address: String) -> Result<Self> {
+        Self::parse(&address)
+    }
+}
+
+impl Deref for EmailAddress {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.address
+    }
+}
+
+impl AsRef<str> for EmailAddress {
+    fn as_ref(&self) -> &str {
+        &self.address
+    }
+}
+
+impl PartialEq for EmailAddress {
+    fn eq(&self, other: &Self) -> bool {
+        self.address.eq_ignore_ascii_case(&other.address)
+    }
+}
+
+impl Eq for EmailAddress {}
+
+impl PartialEq<str> for EmailAddress {
+    fn eq(&self, other: &str) -> bool {
+        self.address.eq_ignore_ascii_case(other)
+    }
+}
+
+impl PartialEq<&str> for EmailAddress {
+    fn eq(&self, other: &&str) -> bool {
+        self.address.eq_ignore_ascii_case(other)
+    }
+}
+
+impl Hash for EmailAddress {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for byte in self.address.bytes() {
+            state.write_u8(byte.to_ascii_lowercase());
+        }
+    }
+}
+
+/// The address GuerrillaMail assigned when asked for an alias.
+///
+/// GuerrillaMail may hand out a different alias than requested, for example after lowercasing
+/// or stripping characters, or when the alias is taken. Returned by
+/// [`Client::create_email`](crate::Client::create_email) so callers can tell the two cases
+/// apart. Dereferences to the assigned address, so it can be passed straight to the other
+/// client methods.
+///
+/// # Example
+/// ```no_run
+/// # use guerrillamail_client::{Assigned, Client};
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), guerrillamail_client::Error> {
+/// let client = Client::new().await?;
+/// let email = client.create_email("signup-test").await?;
+/// if let Assigned::Modified { requested, actual } = &email {
+///     eprintln!("asked for {requested}, got {actual}");
+/// }
+/// let messages = client.get_messages(&email).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Assigned {
+    /// The assigned address uses the requested alias (ignoring ASCII case).
+    Exact(String),
+    /// GuerrillaMail assigned an address with a different alias.
+    Modified {
+        /// The alias that was requested.
+        requested: String,
+        /// The full address that was assigned.
+        actual: String,
+    },
+}
+
+impl Assigned {
+    /// Classify `address` as assigned for the `requested` alias.
+    pub(crate) fn new(requested: &str, address: String) -> Self {
+        let alias = address.split('@').next().unwrap_or(&address);
+        if alias.eq_ignore_ascii_case(requested) {
+            Assigned::Exact(address)
+        } else {
+            Assigned::Modified {
+                requested: requested.to_string(),
+                actual: address,
+            }
+        }
+    }
+
+    /// The full address that was assigned.
+    pub fn address(&self) -> &str {
+        match self {
+            Assigned::Exact(address)
+            | Assigned::Modified {
+                actual: address, ..
+            } => address,
+        }
+    }
+
+    /// Whether the assigned address uses the requested alias.
+    pub fn is_exact(&self) -> bool {
+        matches!(self, Assigned::Exact(_))
+    }
+
+    /// Consume the result, returning the assigned address.
+    pub fn into_address(self) -> String {
+        match self {
+            Assigned::Exact(address)
+            | Assigned::Modified {
+                actual: address, ..
+            } => address,
+        }
+    }
+}
+
+impl fmt::Display for Assigned {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.address())
+    }
+}
+
+impl Deref for Assigned {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.address()
+    }
+}
+
+impl AsRef<str> for Assigned {
+    fn as_ref(&self) -> &str {
+        self.address()
+    }
+}
+
+impl PartialEq<str> for Assigned {
+    fn eq(&self, other: &str) -> bool {
+        self.address() == other
+    }
+}
+
+impl PartialEq<&str> for Assigned {
+    fn eq(&self, other: &&str) -> bool {
+        self.address() == *other
+    }
+}
+
+impl From<Assigned> for String {
+    fn from(assigned: Assigned) -> Self {
+        assigned.into_address()
+    }
+}
+
+/// The alias of `email`, which is either a full address or a bare alias.
+///
+/// Bare aliases (no `@`) are passed through; anything with an `@` must be a valid address.
+pub(crate) fn alias_of(email: &str) -> Result<&str> {
+    if !email.contains('@') {
+        if email.is_empty() || email.chars().any(|c| c.is_whitespace() || c.is_control()) {
+            return Err(Error::InvalidAddress {
+                address: email.to_string(),
+                reason: "empty alias or whitespace in alias",
+            });
+        }
+        return Ok(email);
+    }
+    let at = EmailAddress::parse(email)?.at;
+    Ok(&email[..at])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_addresses_and_rejects_malformed_ones() {
+        let address = EmailAddress::parse("Alias@Example.com").unwrap();
+        assert_eq!(
+            (address.alias(), address.domain()),
+            ("Alias", "Example.com")
+        );
+        assert_eq!(address, "alias@example.com");
+        assert_eq!(address.to_string(), "Alias@Example.com");
+
+        let malformed = [
+            "alias",
+            "@example.com",
+            "a@b@example.com",
+            "a@example",
+            "a@.com",
+            "a b@x.com",
+        ];
+        for bad in malformed {
+            let err = EmailAddress::parse(bad).unwrap_err();
+            assert!(matches!(err, Error::InvalidAddress { .. }), "{bad}: {err}");
+        }
+
+        assert_eq!(alias_of("alias").unwrap(), "alias");
+        assert_eq!(alias_of("alias@example.com").unwrap(), "alias");
+        assert!(alias_of("").is_err());
+        assert!(alias_of("alias@").is_err());
+    }
+
+    #[test]
+    fn assigned_addresses_report_alias_rewrites() {
+        let exact = Assigned::new("Signup", "signup@example.com".to_string());
+        assert!(exact.is_exact());
+        assert_eq!(&*exact, "signup@example.com");
+
+        let modified = Assigned::new("sign up", "signup@example.com".to_string());
+        assert_eq!(
+            modified,
+            Assigned::Modified {
+                requested: "sign up".to_string(),
+                actual: "signup@example.com".to_string(),
+            }
+        );
+        assert_eq!(modified.into_address(), "signup@example.com");
+    }
+}
@@ -0,0 +1,276 @@
+//! Alias (local-part) generation and validation.
+//!
+//! [`validate`] applies the rules the client checks before asking GuerrillaMail for an address;
+//! [`unique`] and [`AliasGenerator`] produce aliases for collision-resistant inboxes.
+
+use crate::{Error, Result};
+use rand::Rng;
+use web_time::{SystemTime, UNIX_EPOCH};
+
+const DEFAULT_CHARSET: &str = "abcdefghijklmnopqrstuvwxyz0123456789";
+const DEFAULT_LENGTH: usize = 12;
+
+/// Longest local part allowed by RFC 5321.
+pub const MAX_LEN: usize = 64;
+
+/// Role mailbox names (RFC 2142 and common administrative ones) that are never handed out.
+const RESERVED: &[&str] = &[
+    "abuse",
+    "admin",
+    "administrator",
+    "hostmaster",
+    "mailer-daemon",
+    "noc",
+    "postmaster",
+    "root",
+    "security",
+    "webmaster",
+];
+
+/// Check that `alias` can be used as a GuerrillaMail local part as-is.
+///
+/// GuerrillaMail silently rewrites aliases it does not accept, so the client runs this check
+/// before every `set_email_user` call. An alias is valid when it is 1 to 64 characters of ASCII
+/// letters, digits, `.`, `_`, `-`, and `+`, does not start or end with `.` or contain `..`, and
+/// is not a reserved role name such as `postmaster`.
+///
+/// # Errors
+/// Returns `Error::InvalidAlias` describing the first rule `alias` breaks.
+///
+/// # Example
+/// ```
+/// use guerrillamail_client::alias;
+///
+/// assert!(alias::validate("signup.test-1+ci").is_ok());
+/// assert!(alias::validate("two words").is_err());
+/// ```
+pub fn validate(alias: &str) -> Result<()> {
+    let invalid = |reason: String| {
+        Err(Error::InvalidAlias {
+            alias: alias.to_string(),
+            reason,
+        })
+    };
+    if alias.is_empty() {
+        return invalid("alias is empty".to_string());
+    }
+    if alias.len() > MAX_LEN {
+        return invalid(format!(
+            "alias is {} characters long; use at most {MAX_LEN}",
+            alias.len()
+        ));
+    }
+    if let Some(c) = alias
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | '+')))
+    {
+        return invalid(format!(
+            "alias contains {c:?}; use only letters, digits, '.', '_', '-', and '+'"
+        ));
+    }
+    if alias.starts_with('.') || alias.ends_with('.') || alias.contains("..") {
+        return invalid("alias cannot start or end with '.' or contain '..'".to_string());
+    }
+    if RESERVED.iter().any(|name| name.eq_ignore_ascii_case(alias)) {
+        return invalid(format!("`{alias}` is a reserved mailbox name"));
+    }
+    Ok(())
+}
+
+/// Length of the random part of [`unique`] aliases.
+const UNIQUE_SUFFIX_LEN: usize = 6;
+
+/// An alias that no other process will pick: `prefix`, a timestamp, and a random suffix.
+///
+/// The result looks like `ci-shard3-m1x2k9qz-4fj2ka`: the prefix keeps inboxes recognizable, the
+/// base-36 millisecond timestamp separates runs, and the random suffix separates parallel CI
+/// shards started in the same millisecond. The prefix is lowercased, stripped of characters
+/// other than ASCII letters, digits, `-`, and `_`, and shortened as needed, so the alias always
+/// passes [`validate`] and stays within [`MAX_LEN`].
+///
+/// # Example
+/// ```
+/// use guerrillamail_client::alias;
+///
+/// let alias = alias::unique("CI shard 3");
+/// assert!(alias.starts_with("cishard3-"));
+/// assert!(alias::validate(&alias).is_ok());
+/// assert_ne!(alias, alias::unique("CI shard 3"));
+/// ```
+pub fn unique(prefix: &str) -> String {
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis());
+    let suffix = AliasGenerator::new().length(UNIQUE_SUFFIX_LEN).generate();
+    let stamp = format!("{}-{suffix}", base36(millis));
+
+    let room = MAX_LEN - stamp.len() - 1;
+    let prefix: String = prefix
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+        .map(|c| c.to_ascii_lowercase())
+        .take(room)
+        .collect();
+    if prefix.is_empty() {
+        stamp
+    } else {
+        format!("{prefix}-{stamp}")
+    }
+}
+
+/// Format `value` in lowercase base 36.
+fn base36(mut value: u128) -> String {
+    const DIGITS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+    let mut digits = Vec::new();
+    loop {
+        digits.push(DIGITS[(value % 36) as usize]);
+        value /= 36;
+        if value == 0 {
+            break;
+        }
+    }
+    digits.reverse();
+    String::from_utf8(digits).expect("base-36 digits are ASCII")
+}
+
+/// Generates random aliases (local-parts) for new GuerrillaMail addresses.
+///
+/// The default produces 12 lowercase alphanumeric characters (~62 bits of entropy), which is
+/// enough for parallel CI jobs to pick aliases independently without colliding. The random
+/// part can be tuned via [`length`](AliasGenerator::length) and
+/// [`charset`](AliasGenerator::charset), and an optional [`prefix`](AliasGenerator::prefix) is
+/// prepended verbatim.
+///
+/// # Example
+/// ```
+/// use guerrillamail_client::AliasGenerator;
+///
+/// let alias = AliasGenerator::new().prefix("ci-").length(8).generate();
+/// assert!(alias.starts_with("ci-"));
+/// assert_eq!(alias.len(), 11);
+/// ```
+#[derive(Debug, Clone)]
+pub struct AliasGenerator {
+    prefix: String,
+    length: usize,
+    charset: Vec<char>,
+}
+
+impl Default for AliasGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AliasGenerator {
+    /// Create a generator with the default length and lowercase alphanumeric charset.
+    pub fn new() -> Self {
+        Self {
+            prefix: String::new(),
+            length: DEFAULT_LENGTH,
+            charset: DEFAULT_CHARSET.chars().collect(),
+        }
+    }
+
+    /// Set a fixed prefix placed before the random part (default: none).
+    pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// Set the number of random characters (default: 12).
+    ///
+    /// # Panics
+    /// Panics if `length` is zero.
+    pub fn length(mut self, length: usize) -> Self {
+        assert!(length > 0, "alias length must be non-zero");
+        self.length = length;
+        self
+    }
+
+    /// Set the characters the random part is drawn from.
+    ///
+    /// # Panics
+    /// Panics if `charset` is empty.
+    pub fn charset(mut self, charset: &str) -> Self {
+        assert!(!charset.is_empty(), "alias charset must not be empty");
+        self.charset = charset.chars().collect();
+        self
+    }
+
+    /// Generate a new alias.
+    pub fn generate(&self) -> String {
+        let mut rng = rand::rng();
+        let mut alias = String::with_capacity(self.prefix.len() + self.length);
+        alias.push_str(&self.prefix);
+        alias.extend(
+            (0..self.length).map(|_| self.charset[rng.random_range(0..self.charset.len())]),
+        );
+        alias
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_rejects_aliases_guerrillamail_would_rewrite() {
+        assert!(validate("signup.test-1+ci").is_ok());
+        assert!(validate(&"a".repeat(MAX_LEN)).is_ok());
+
+        for bad in [
+            "",
+            ".hidden",
+            "trailing.",
+            "a..b",
+            "two words",
+            "ünïcode",
+            "PostMaster",
+        ] {
+            assert!(
+                matches!(validate(bad), Err(Error::InvalidAlias { .. })),
+                "{bad}"
+            );
+        }
+        let err = validate(&"a".repeat(MAX_LEN + 1)).unwrap_err();
+        assert!(err.to_string().contains("use at most 64"));
+    }
+
+    #[test]
+    fn unique_aliases_are_valid_distinct_and_bounded() {
+        let alias = unique("ci.run+7");
+        assert!(alias.starts_with("cirun7-"));
+        assert!(validate(&alias).is_ok());
+        assert_ne!(alias, unique("ci.run+7"));
+
+        let long = unique(&"x".repeat(100));
+        assert_eq!(long.len(), MAX_LEN);
+        assert!(validate(&long).is_ok());
+
+        let bare = unique("");
+        assert!(bare.chars().next().unwrap().is_ascii_alphanumeric());
+        assert_eq!(base36(36 * 36 + 35), "10z");
+    }
+
+    #[test]
+    fn generate_respects_length_charset_and_prefix() {
+        let alias = AliasGenerator::new()
+            .prefix("ci-")
+            .length(20)
+            .charset("ab")
+            .generate();
+
+        assert_eq!(alias.len(), 23);
+        assert!(alias.starts_with("ci-"));
+        assert!(alias[3..].chars().all(|c| c == 'a' || c == 'b'));
+    }
+
+    #[test]
+    fn generate_defaults_to_lowercase_alphanumeric() {
+        let alias = AliasGenerator::new().generate();
+
+        assert_eq!(alias.len(), DEFAULT_LENGTH);
+        assert!(alias.chars().all(|c| DEFAULT_CHARSET.contains(c)));
+    }
+}
@@ -0,0 +1,219 @@
+//! Settings shared by every invocation: `~/.config/guerrillamail/config.toml`, then the
+//! `[profiles.<name>]` table chosen with `--profile`, then `GUERRILLAMAIL_*` environment
+//! variables; later sources take precedence.
+//!
+//! ```toml
+//! proxy = "socks5h://127.0.0.1:9050"
+//! site = "sharklasers.com"
+//! user_agent = "qa-bot/1.0"
+//!
+//! [profiles.work]
+//! proxy = "http://10.0.0.2:3128"
+//! session = "~/.local/state/guerrillamail/work-session.json"
+//! ```
+
+use guerrillamail_client::{ClientBuilder, Session};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Client settings read from the config file and environment.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Proxy URL for all requests (`GUERRILLAMAIL_PROXY`).
+    pub proxy: Option<String>,
+    /// GuerrillaMail site to advertise (`GUERRILLAMAIL_SITE`).
+    pub site: Option<String>,
+    /// User-Agent header (`GUERRILLAMAIL_UA`).
+    pub user_agent: Option<String>,
+    /// Session file to resume from and save back to, keeping one identity across runs.
+    pub session: Option<PathBuf>,
+}
+
+/// The config file: default settings plus named profiles.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct File {
+    proxy: Option<String>,
+    site: Option<String>,
+    user_agent: Option<String>,
+    session: Option<PathBuf>,
+    #[serde(default)]
+    profiles: BTreeMap<String, Config>,
+}
+
+impl Config {
+    /// Read the config file, if any, select `profile`, and apply environment overrides.
+    pub fn load(profile: Option<&str>) -> Result<Self, String> {
+        let path = path();
+        let text = match &path {
+            Some(path) if path.exists() => std::fs::read_to_string(path)
+                .map_err(|err| format!("reading {}: {err}", path.display()))?,
+            _ => String::new(),
+        };
+        let mut config = Self::parse(&text, profile).map_err(|err| match &path {
+            Some(path) => format!("{}: {err}", path.display()),
+            None => err,
+        })?;
+        config.merge_env(|name| std::env::var(name).ok());
+        Ok(config)
+    }
+
+    /// Parse the TOML config file format and select `profile` from it.
+    pub fn parse(text: &str, profile: Option<&str>) -> Result<Self, String> {
+        let mut file: File = toml::from_str(text).map_err(|err| err.to_string())?;
+        let mut config = Self {
+            proxy: file.proxy,
+            site: file.site,
+            user_agent: file.user_agent,
+            session: file.session,
+        };
+        if let Some(name) = profile {
+            let Some(profile) = file.profiles.remove(name) else {
+                let known: Vec<_> = file.profiles.keys().map(String::as_str).collect();
+                return Err(if known.is_empty() {
+                    format!("unknown profile {name:?}; no profiles are defined")
+                } else {
+                    format!("unknown profile {name:?}; defined: {}", known.join(", "))
+                });
+            };
+            config.proxy = profile.proxy.or(config.proxy);
+            config.site = profile.site.or(config.site);
+            config.user_agent = profile.user_agent.or(config.user_agent);
+            config.session = profile.session.or(config.session);
+        }
+        config.session = config.session.map(expand_home);
+        Ok(config)
+    }
+
+    /// The saved session, if a session file is configured and already exists.
+    pub fn saved_session(&self) -> Result<Option<Session>, String> {
+        match &self.session {
+            Some(path) if path.exists() => Session::load(path)
+                .map(Some)
+                .map_err(|err| format!("reading {}: {err}", path.display())),
+            _ => Ok(None),
+        }
+    }
+
+    /// Write `session` to the configured session file, if any.
+    pub fn save_session(&self, session: &Session) -> Result<(), String> {
+        let Some(path) = &self.session else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)
+                .map_err(|err| format!("creating {}: {err}", dir.display()))?;
+        }
+        session
+            .save(path)
+            .map_err(|err| format!("writing {}: {err}", path.display()))
+    }
+
+    /// Override settings with the non-empty environment variables returned by `var`.
+    pub fn merge_env(&mut self, var: impl Fn(&str) -> Option<String>) {
+        let var = |name| var(name).filter(|value: &String| !value.is_empty());
+        if let Some(proxy) = var("GUERRILLAMAIL_PROXY") {
+            self.proxy = Some(proxy);
+        }
+        if let Some(site) = var("GUERRILLAMAIL_SITE") {
+            self.site = Some(site);
+        }
+        if let Some(user_agent) = var("GUERRILLAMAIL_UA") {
+            self.user_agent = Some(user_agent);
+        }
+    }
+
+    /// Configure `builder` with every setting that is present, except the session file.
+    pub fn apply(&self, mut builder: ClientBuilder) -> ClientBuilder {
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(proxy);
+        }
+        if let Some(site) = &self.site {
+            builder = builder.site(site);
+        }
+        if let Some(user_agent) = &self.user_agent {
+            builder = builder.user_agent(user_agent);
+        }
+        builder
+    }
+}
+
+/// `path` with a leading `~/` replaced by the home directory.
+fn expand_home(path: PathBuf) -> PathBuf {
+    match (path.strip_prefix("~"), std::env::var_os("HOME")) {
+        (Ok(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => path,
+    }
+}
+
+/// `$XDG_CONFIG_HOME/guerrillamail/config.toml`, falling back to `~/.config`.
+fn path() -> Option<PathBuf> {
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_home.join("guerrillamail").join("config.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn environment_overrides_the_config_file() {
+        let mut config = Config::parse(
+            r#"
+            proxy = "http://127.0.0.1:8080"
+            site = "sharklasers.com"
+            "#,
+            None,
+        )
+        .unwrap();
+
+        config.merge_env(|name| match name {
+            "GUERRILLAMAIL_PROXY" => Some("socks5h://127.0.0.1:9050".to_string()),
+            "GUERRILLAMAIL_SITE" => Some(String::new()),
+            "GUERRILLAMAIL_UA" => Some("qa-bot/1.0".to_string()),
+            _ => None,
+        });
+
+        assert_eq!(
+            config,
+            Config {
+                proxy: Some("socks5h://127.0.0.1:9050".to_string()),
+                site: Some("sharklasers.com".to_string()),
+                user_agent: Some("qa-bot/1.0".to_string()),
+                session: None,
+            }
+        );
+        assert!(Config::parse("proxi = \"typo\"", None).is_err());
+    }
+
+    #[test]
+    fn profiles_override_the_defaults() {
+        let text = r#"
+            proxy = "http://127.0.0.1:8080"
+            user_agent = "qa-bot/1.0"
+
+            [profiles.work]
+            proxy = "http://10.0.0.2:3128"
+            session = "/tmp/work.json"
+
+            [profiles.home]
+            site = "sharklasers.com"
+        "#;
+
+        let work = Config::parse(text, Some("work")).unwrap();
+        assert_eq!(work.proxy.as_deref(), Some("http://10.0.0.2:3128"));
+        assert_eq!(work.user_agent.as_deref(), Some("qa-bot/1.0"));
+        assert_eq!(work.session, Some(PathBuf::from("/tmp/work.json")));
+
+        let default = Config::parse(text, None).unwrap();
+        assert_eq!(default.proxy.as_deref(), Some("http://127.0.0.1:8080"));
+
+        let err = Config::parse(text, Some("play")).unwrap_err();
+        assert_eq!(err, r#"unknown profile "play"; defined: home, work"#);
+    }
+}
@@ -0,0 +1,252 @@
+//! `guerrillamail doctor`: step-by-step connectivity checks with hints for each failure.
+
+use crate::CliResult;
+use guerrillamail_client::{ClientBuilder, Error};
+use serde_json::{Value, json};
+use std::error::Error as _;
+use std::time::Duration;
+
+/// Host every GuerrillaMail request goes to.
+const HOST: &str = "www.guerrillamail.com";
+
+/// How long the DNS and TCP checks may take.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Outcome of one check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Ok,
+    Skipped,
+    Failed,
+}
+
+/// Collected results, printed by the caller.
+#[derive(Debug, Default)]
+pub struct Report {
+    checks: Vec<Value>,
+}
+
+impl Report {
+    fn add(&mut self, check: &str, status: Status, detail: impl Into<String>, hint: &str) {
+        let status = match status {
+            Status::Ok => "ok",
+            Status::Skipped => "skipped",
+            Status::Failed => "failed",
+        };
+        self.checks.push(json!({
+            "check": check,
+            "status": status,
+            "detail": detail.into(),
+            "hint": hint,
+        }));
+    }
+
+    /// One JSON object per check: `check`, `status`, `detail`, and `hint`.
+    pub fn checks(&self) -> &[Value] {
+        &self.checks
+    }
+
+    /// Whether any check failed.
+    pub fn failed(&self) -> bool {
+        self.checks.iter().any(|check| check["status"] == "failed")
+    }
+}
+
+/// Run every check, stopping at the first failure that makes later ones meaningless.
+///
+/// `proxied` skips the direct DNS and TCP checks, since the proxy resolves and connects.
+pub async fn run(builder: ClientBuilder, proxied: bool) -> CliResult<Report> {
+    let mut report = Report::default();
+
+    if proxied {
+        report.add(
+            "dns",
+            Status::Skipped,
+            "a proxy is configured; it resolves hosts",
+            "",
+        );
+        report.add(
+            "tcp",
+            Status::Skipped,
+            "a proxy is configured; it connects",
+            "",
+        );
+    } else {
+        let lookup = tokio::time::timeout(CHECK_TIMEOUT, tokio::net::lookup_host((HOST, 443)));
+        let addr = match lookup.await {
+            Ok(Ok(mut addrs)) => addrs.next(),
+            Ok(Err(err)) => {
+                let hint = "check the DNS resolver, or use --proxy / --tor to resolve remotely";
+                report.add("dns", Status::Failed, err.to_string(), hint);
+                return Ok(report);
+            }
+            Err(_) => None,
+        };
+        let Some(addr) = addr else {
+            report.add(
+                "dns",
+                Status::Failed,
+                format!("no address for {HOST}"),
+                "check DNS",
+            );
+            return Ok(report);
+        };
+        report.add("dns", Status::Ok, format!("{HOST} -> {}", addr.ip()), "");
+
+        let connect = tokio::time::timeout(CHECK_TIMEOUT, tokio::net::TcpStream::connect(addr));
+        match connect.await {
+            Ok(Ok(_)) => report.add("tcp", Status::Ok, format!("connected to {addr}"), ""),
+            Ok(Err(err)) => {
+                let hint = "a firewall blocks outbound HTTPS; route through --proxy";
+                report.add("tcp", Status::Failed, err.to_string(), hint);
+                return Ok(report);
+            }
+            Err(_) => {
+                let hint = "the connection hangs; a firewall may drop HTTPS, try --proxy";
+                report.add("tcp", Status::Failed, "timed out", hint);
+                return Ok(report);
+            }
+        }
+    }
+
+    // Building the client asks the API for a session over TLS, scraping the homepage if needed.
+    let client = match builder.build().await {
+        Ok(client) => {
+            report.add("tls", Status::Ok, "certificate verified", "");
+            report.add("bootstrap", Status::Ok, "session token obtained", "");
+            client
+        }
+        Err(err) => {
+            let (check, hint) = diagnose(&err);
+            if check == "bootstrap" {
+                report.add("tls", Status::Ok, "certificate verified", "");
+            }
+            report.add(check, Status::Failed, chain(&err), hint);
+            return Ok(report);
+        }
+    };
+
+    match client.current_address().await {
+        Ok(address) => {
+            let detail = format!("ajax.php answered; session address {}", address.email_addr);
+            report.add("ajax", Status::Ok, detail, "");
+        }
+        Err(err) => {
+            let (_, hint) = diagnose(&err);
+            report.add("ajax", Status::Failed, chain(&err), hint);
+        }
+    }
+    Ok(report)
+}
+
+/// The check a bootstrap failure belongs to, and what to do about it.
+fn diagnose(err: &Error) -> (&'static str, &'static str) {
+    match err {
+        Error::ChallengeDetected { .. } => (
+            "bootstrap",
+            "Cloudflare or another anti-bot wall answered; switch proxy or wait before retrying",
+        ),
+        Error::TokenParse | Error::DomainParse => (
+            "bootstrap",
+            "the homepage layout changed or a captive portal answered; update this crate",
+        ),
+        Error::RateLimited { .. } => ("bootstrap", "rate limited; wait or use another proxy"),
+        Error::Api { status, .. } if status.as_u16() == 403 => (
+            "bootstrap",
+            "HTTP 403 usually means this IP is blocked; try another egress address",
+        ),
+        Error::Api { .. } => ("bootstrap", "GuerrillaMail returned an error; retry later"),
+        Error::Request {
+            source: request, ..
+        } if request.is_timeout() => (
+            "tls",
+            "the request timed out; check the proxy or network latency",
+        ),
+        Error::Request { .. } if mentions(err, &["certificate", "tls", "ssl", "handshake"]) => (
+            "tls",
+            "the certificate was rejected; an intercepting proxy needs its CA trusted",
+        ),
+        Error::Request {
+            source: request, ..
+        } if request.is_connect() => {
+            if mentions(err, &["dns", "resolve", "lookup", "name or service"]) {
+                (
+                    "dns",
+                    "the host name did not resolve; check DNS or use --proxy / --tor",
+                )
+            } else if mentions(err, &["proxy", "socks"]) {
+                (
+                    "proxy",
+                    "the proxy failed the connection; check its address and credentials",
+                )
+            } else {
+                (
+                    "tcp",
+                    "the connection failed; a firewall may block HTTPS, try --proxy",
+                )
+            }
+        }
+        Error::Request { .. } => (
+            "bootstrap",
+            "the request failed after connecting; retry, or report it with this output",
+        ),
+        _ => (
+            "bootstrap",
+            "unexpected failure; please report it with this output",
+        ),
+    }
+}
+
+/// Whether any error in the chain mentions one of `words` (lowercase).
+fn mentions(err: &Error, words: &[&str]) -> bool {
+    let mut source: Option<&dyn std::error::Error> = Some(err);
+    while let Some(err) = source {
+        let text = err.to_string().to_ascii_lowercase();
+        if words.iter().any(|word| text.contains(word)) {
+            return true;
+        }
+        source = err.source();
+    }
+    false
+}
+
+/// `err` followed by each of its sources, separated by `: `.
+fn chain(err: &Error) -> String {
+    let mut text = err.to_string();
+    let mut source = err.source();
+    while let Some(err) = source {
+        text.push_str(": ");
+        text.push_str(&err.to_string());
+        source = err.source();
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn challenge_pages_point_at_the_proxy() {
+        let err = Error::ChallengeDetected {
+            body_excerpt: "Just a moment...".to_string(),
+            request_id: None,
+        };
+        let (check, hint) = diagnose(&err);
+
+        assert_eq!(check, "bootstrap");
+        assert!(hint.contains("Cloudflare"));
+    }
+
+    #[tokio::test]
+    async fn refused_connections_are_not_blamed_on_tls() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+        let err = reqwest::get(format!("http://{addr}/")).await.unwrap_err();
+        let (check, hint) = diagnose(&Error::from(err));
+
+        assert_eq!(check, "tcp");
+        assert!(hint.contains("firewall"));
+    }
+}
@@ -0,0 +1,303 @@
+//! RFC 5322 messages and mboxrd archives built from fetched mail.
+
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD;
+use clap::ValueEnum;
+use guerrillamail_client::EmailDetails;
+use std::time::{Duration, UNIX_EPOCH};
+
+/// Archive formats for the `export` command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ArchiveFormat {
+    /// One `<mail_id>.eml` file per message in the output directory.
+    Eml,
+    /// A single mboxrd file holding every message.
+    Mbox,
+}
+
+/// Longest line allowed by RFC 5322, excluding the CRLF.
+const MAX_LINE: usize = 998;
+
+/// Render `details`, received at `to`, as an RFC 5322 message with CRLF line endings.
+///
+/// `attachments` pairs each attachment's filename and content type with its bytes; with any
+/// present the message becomes `multipart/mixed`.
+pub fn eml(to: &str, details: &EmailDetails, attachments: &[(String, String, Vec<u8>)]) -> String {
+    let mut out = String::new();
+    header(&mut out, "From", &mailbox(&details.mail_from));
+    header(&mut out, "To", to);
+    header(&mut out, "Subject", &encode_word(&details.mail_subject));
+    header(&mut out, "Date", &rfc5322_date(timestamp(details)));
+    header(
+        &mut out,
+        "Message-ID",
+        &format!("<{}@guerrillamail.com>", details.mail_id),
+    );
+    header(&mut out, "MIME-Version", "1.0");
+
+    if attachments.is_empty() {
+        body_part(&mut out, &details.mail_body);
+        return out;
+    }
+    let boundary = format!("=_guerrillamail_{}", details.mail_id);
+    header(
+        &mut out,
+        "Content-Type",
+        &format!("multipart/mixed; boundary=\"{boundary}\""),
+    );
+    out.push_str("\r\n");
+    out.push_str(&format!("--{boundary}\r\n"));
+    body_part(&mut out, &details.mail_body);
+    for (filename, content_type, bytes) in attachments {
+        out.push_str(&format!("\r\n--{boundary}\r\n"));
+        // The type comes from the server's hint, so keep CR/LF from starting a new header.
+        let content_type: String = content_type.chars().filter(|c| !c.is_control()).collect();
+        header(&mut out, "Content-Type", &content_type);
+        header(&mut out, "Content-Transfer-Encoding", "base64");
+        header(
+            &mut out,
+            "Content-Disposition",
+            &format!("attachment; {}", filename_param(filename)),
+        );
+        out.push_str("\r\n");
+        out.push_str(&base64_lines(bytes));
+    }
+    out.push_str(&format!("\r\n--{boundary}--\r\n"));
+    out
+}
+
+/// Append `message` (as produced by [`eml`]) to an mboxrd archive.
+///
+/// Lines are converted to LF, a `From ` separator line is added, and body lines matching
+/// `>*From ` gain one more `>` so readers can undo the quoting.
+pub fn append_mbox(mbox: &mut String, sender: &str, details: &EmailDetails, message: &str) {
+    let sender = match split_mailbox(sender) {
+        Some((_, address)) => address,
+        None => sender.trim().to_string(),
+    };
+    let sender = if sender.is_empty() || sender.contains(char::is_whitespace) {
+        "MAILER-DAEMON"
+    } else {
+        &sender
+    };
+    mbox.push_str(&format!("From {sender} {}\n", asctime(timestamp(details))));
+    for line in message.split("\r\n") {
+        if line.trim_start_matches('>').starts_with("From ") {
+            mbox.push('>');
+        }
+        mbox.push_str(line);
+        mbox.push('\n');
+    }
+}
+
+fn header(out: &mut String, name: &str, value: &str) {
+    out.push_str(&format!("{name}: {value}\r\n"));
+}
+
+/// The HTML body as a single part, base64-encoded when it is unsafe to send as 8-bit text.
+fn body_part(out: &mut String, body: &str) {
+    header(out, "Content-Type", "text/html; charset=utf-8");
+    let lines: Vec<&str> = body.lines().collect();
+    if lines.iter().all(|line| line.len() <= MAX_LINE) && !body.contains('\r') {
+        header(out, "Content-Transfer-Encoding", "8bit");
+        out.push_str("\r\n");
+        for line in lines {
+            out.push_str(line);
+            out.push_str("\r\n");
+        }
+    } else {
+        header(out, "Content-Transfer-Encoding", "base64");
+        out.push_str("\r\n");
+        out.push_str(&base64_lines(body.as_bytes()));
+    }
+}
+
+/// Whether `text` can appear in a header as is.
+fn is_printable_ascii(text: &str) -> bool {
+    text.chars().all(|c| c == ' ' || c.is_ascii_graphic())
+}
+
+/// `text` unchanged if it is printable ASCII, otherwise as an RFC 2047 base64 encoded-word.
+fn encode_word(text: &str) -> String {
+    if is_printable_ascii(text) {
+        return text.to_string();
+    }
+    format!("=?utf-8?b?{}?=", STANDARD.encode(text))
+}
+
+/// A `From` value with only the display name encoded, since encoded-words may not appear inside
+/// the address itself (RFC 2047, section 5).
+fn mailbox(from: &str) -> String {
+    let Some((name, address)) = split_mailbox(from) else {
+        return encode_word(from.trim());
+    };
+    let name = name.trim().trim_matches('"').trim();
+    if name.is_empty() {
+        return format!("<{address}>");
+    }
+    format!("{} <{address}>", phrase(name))
+}
+
+/// The display name and address of a `name <address>` value, without control characters in the
+/// address.
+fn split_mailbox(from: &str) -> Option<(&str, String)> {
+    let (name, address) = from.trim().strip_suffix('>')?.rsplit_once('<')?;
+    let address = address.trim().chars().filter(|c| !c.is_control()).collect();
+    Some((name, address))
+}
+
+/// A display name as an RFC 5322 phrase: encoded if it is not ASCII, quoted if it holds
+/// specials.
+fn phrase(name: &str) -> String {
+    if !is_printable_ascii(name) {
+        return encode_word(name);
+    }
+    if name.chars().any(|c| "()<>[]:;@\\,.\"".contains(c)) {
+        return format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""));
+    }
+    name.to_string()
+}
+
+/// The `filename` parameter of a `Content-Disposition` header, in RFC 2231 extended notation
+/// when `filename` is not plain ASCII.
+fn filename_param(filename: &str) -> String {
+    if is_printable_ascii(filename) {
+        return format!("filename=\"{}\"", filename.replace(['"', '\\'], "_"));
+    }
+    let mut encoded = String::with_capacity(filename.len() * 3);
+    for byte in filename.bytes() {
+        if byte.is_ascii_alphanumeric() || b"!#$&+-.^_`|~".contains(&byte) {
+            encoded.push(char::from(byte));
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    format!("filename*=UTF-8''{encoded}")
+}
+
+fn timestamp(details: &EmailDetails) -> u64 {
+    details.mail_timestamp.trim().parse().unwrap_or(0)
+}
+
+/// `Sun, 06 Nov 1994 08:49:37 +0000`.
+fn rfc5322_date(secs: u64) -> String {
+    let http = httpdate::fmt_http_date(UNIX_EPOCH + Duration::from_secs(secs));
+    http.replace(" GMT", " +0000")
+}
+
+/// `Sun Nov  6 08:49:37 1994`, as used on mbox `From ` lines.
+fn asctime(secs: u64) -> String {
+    let http = httpdate::fmt_http_date(UNIX_EPOCH + Duration::from_secs(secs));
+    let parts: Vec<&str> = http.split_whitespace().collect();
+    let [weekday, day, month, year, time, _zone] = parts[..] else {
+        unreachable!("httpdate always formats six fields");
+    };
+    let day = day.trim_start_matches('0');
+    format!(
+        "{} {month} {day:>2} {time} {year}",
+        weekday.trim_end_matches(',')
+    )
+}
+
+/// Base64 wrapped at 76 characters per line, as MIME requires.
+fn base64_lines(bytes: &[u8]) -> String {
+    let encoded = STANDARD.encode(bytes);
+    let mut out = String::with_capacity(encoded.len() + encoded.len() / 38 + 2);
+    for line in encoded.as_bytes().chunks(76) {
+        out.push_str(std::str::from_utf8(line).expect("base64 is ASCII"));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn details(body: &str) -> EmailDetails {
+        serde_json::from_value(serde_json::json!({
+            "mail_id": "42",
+            "mail_from": "noreply@example.com",
+            "mail_subject": "Grüße",
+            "mail_body": body,
+            "mail_timestamp": "784111777",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn builds_mime_messages_and_quotes_mbox_from_lines() {
+        let details = details("<p>Hi</p>\nFrom here on\n");
+        let message = eml("qa@sharklasers.com", &details, &[]);
+
+        assert!(message.starts_with("From: noreply@example.com\r\nTo: qa@sharklasers.com\r\n"));
+        assert!(message.contains("Subject: =?utf-8?b?R3LDvMOfZQ==?=\r\n"));
+        assert!(message.contains("Date: Sun, 06 Nov 1994 08:49:37 +0000\r\n"));
+        assert!(message.ends_with("\r\n\r\n<p>Hi</p>\r\nFrom here on\r\n"));
+
+        let mut mbox = String::new();
+        append_mbox(&mut mbox, &details.mail_from, &details, &message);
+        assert!(mbox.starts_with("From noreply@example.com Sun Nov  6 08:49:37 1994\n"));
+        assert!(mbox.contains("\n>From here on\n"));
+        assert!(!mbox.contains('\r'));
+    }
+
+    #[test]
+    fn attachments_become_base64_parts() {
+        let attachment = (
+            "a.txt".to_string(),
+            "text/plain".to_string(),
+            b"hello".to_vec(),
+        );
+        let message = eml("qa@sharklasers.com", &details("body"), &[attachment]);
+
+        assert!(message.contains("multipart/mixed; boundary=\"=_guerrillamail_42\""));
+        assert!(message.contains("filename=\"a.txt\"\r\n\r\naGVsbG8=\r\n"));
+        assert!(message.ends_with("--=_guerrillamail_42--\r\n"));
+    }
+
+    #[test]
+    fn encodes_only_the_display_name_and_uses_rfc_2231_filenames() {
+        let mut details = details("body");
+        details.mail_from = "Jürgen Müller <jm@example.com>".to_string();
+        let attachment = (
+            "Grüße.pdf".to_string(),
+            "application/pdf".to_string(),
+            Vec::new(),
+        );
+        let message = eml("qa@sharklasers.com", &details, &[attachment]);
+
+        assert!(message.starts_with("From: =?utf-8?b?SsO8cmdlbiBNw7xsbGVy?= <jm@example.com>\r\n"));
+        assert!(message.contains("attachment; filename*=UTF-8''Gr%C3%BC%C3%9Fe.pdf\r\n"));
+
+        assert_eq!(
+            mailbox("\"Doe, Jane\" <jd@example.com>"),
+            "\"Doe, Jane\" <jd@example.com>"
+        );
+        assert_eq!(mailbox("<jd@example.com>"), "<jd@example.com>");
+        assert_eq!(mailbox("jd@example.com"), "jd@example.com");
+    }
+
+    #[test]
+    fn mbox_separator_uses_the_address_and_types_stay_one_header() {
+        let mut details = details("body");
+        details.mail_from = "Jane Doe <jane@x.com>".to_string();
+        let attachment = (
+            "a.txt".to_string(),
+            "text/plain\r\nBcc: victim@example.com".to_string(),
+            Vec::new(),
+        );
+        let message = eml("qa@sharklasers.com", &details, &[attachment]);
+
+        assert!(message.contains("Content-Type: text/plainBcc: victim@example.com\r\n"));
+        assert!(!message.contains("\r\nBcc:"));
+
+        let mut mbox = String::new();
+        append_mbox(&mut mbox, &details.mail_from, &details, &message);
+        assert!(mbox.starts_with("From jane@x.com Sun Nov  6 08:49:37 1994\n"));
+
+        let mut mbox = String::new();
+        append_mbox(&mut mbox, "Jane Doe", &details, &message);
+        assert!(mbox.starts_with("From MAILER-DAEMON "));
+    }
+}
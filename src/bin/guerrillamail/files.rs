@@ -0,0 +1,58 @@
+//! Writing downloaded content to disk.
+
+use std::path::{Path, PathBuf};
+
+/// `name` reduced to a single safe path component.
+///
+/// Directory parts are dropped, characters that are reserved on common filesystems or are
+/// control characters become `_`, and leading dots are removed so the file is neither hidden
+/// nor `..`. Falls back to `fallback` when nothing usable remains.
+pub fn sanitize_filename(name: &str, fallback: &str) -> String {
+    let base = name.rsplit(['/', '\\']).next().unwrap_or_default();
+    let cleaned: String = base
+        .chars()
+        .map(|c| match c {
+            '<' | '>' | ':' | '"' | '|' | '?' | '*' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    let cleaned = cleaned.trim_start_matches('.').trim();
+    if cleaned.is_empty() {
+        fallback.to_string()
+    } else {
+        cleaned.chars().take(200).collect()
+    }
+}
+
+/// `dir/name`, or `dir/stem-N.ext` with the first free `N` if that path already exists.
+pub fn unique_path(dir: &Path, name: &str) -> PathBuf {
+    let path = dir.join(name);
+    if !path.exists() {
+        return path;
+    }
+    let (stem, ext) = match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem, format!(".{ext}")),
+        _ => (name, String::new()),
+    };
+    (1..)
+        .map(|n| dir.join(format!("{stem}-{n}{ext}")))
+        .find(|path| !path.exists())
+        .expect("unbounded range")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filenames_cannot_escape_the_output_directory() {
+        assert_eq!(sanitize_filename("report.pdf", "x"), "report.pdf");
+        assert_eq!(sanitize_filename("../../etc/passwd", "x"), "passwd");
+        assert_eq!(sanitize_filename("C:\\Users\\a\\run.bat", "x"), "run.bat");
+        assert_eq!(sanitize_filename("what?<now>.txt", "x"), "what__now_.txt");
+        assert_eq!(sanitize_filename("..", "attachment-2"), "attachment-2");
+        assert_eq!(sanitize_filename(".bashrc", "x"), "bashrc");
+        assert_eq!(sanitize_filename("", "attachment-3"), "attachment-3");
+    }
+}
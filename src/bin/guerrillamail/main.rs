@@ -0,0 +1,625 @@
+//! `guerrillamail`: disposable GuerrillaMail inboxes from the shell.
+//!
+//! Built with the `cli` feature. When the active profile configures a `session` file, each
+//! invocation resumes the session saved there and writes it back afterwards; otherwise it
+//! starts a fresh GuerrillaMail session. Commands that take an address first re-open it in
+//! that session. Proxy, site, User-Agent, and session file come from the config file and
+//! environment; see [`config`]. The `--proxy`, `--socks5`, and `--tor` flags override the
+//! configured proxy.
+
+mod config;
+mod doctor;
+mod export;
+mod files;
+mod output;
+mod registry;
+#[cfg(feature = "tui")]
+mod ui;
+mod validate;
+
+use clap::{Args, CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
+use config::Config;
+use export::ArchiveFormat;
+use guerrillamail_client::{
+    AliasGenerator, Assigned, Client, ClientBuilder, CodePattern, EmailDetails, MultiWatcher,
+    ProxyConfig, WatchEvent,
+};
+use output::{Format, details_json, message_json, row};
+use regex::Regex;
+use serde_json::json;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Duration;
+
+/// Disposable GuerrillaMail inboxes from the shell.
+#[derive(Debug, Parser)]
+#[command(name = "guerrillamail", version)]
+struct Cli {
+    /// Output format.
+    #[arg(long, short, global = true, value_enum, default_value_t)]
+    output: Format,
+    /// Use the settings of `[profiles.<NAME>]` in the config file.
+    #[arg(long, global = true, value_name = "NAME")]
+    profile: Option<String>,
+    #[command(flatten)]
+    network: Network,
+    #[command(subcommand)]
+    command: Command,
+}
+
+/// Arguments shared by the commands that follow an inbox.
+#[derive(Debug, Args)]
+struct Follow {
+    /// Address to follow.
+    #[arg(value_parser = validate::address)]
+    address: String,
+    /// Exit after this many messages.
+    #[arg(long, short = 'n')]
+    count: Option<usize>,
+    /// Give up after this many seconds; fails if `--count` messages have not arrived.
+    #[arg(long)]
+    timeout: Option<u64>,
+    /// Seconds between inbox polls.
+    #[arg(long, default_value_t = 5)]
+    interval: u64,
+    /// Also print messages already in the inbox.
+    #[arg(long)]
+    existing: bool,
+}
+
+/// Egress settings; each overrides the configured proxy.
+#[derive(Debug, Args)]
+#[group(multiple = false)]
+struct Network {
+    /// Send requests through this proxy URL (http://, https://, socks5://, or socks5h://).
+    #[arg(long, global = true, value_name = "URL")]
+    proxy: Option<String>,
+    /// Send requests through a SOCKS5 proxy at HOST:PORT, resolving DNS through it.
+    #[arg(long, global = true, value_name = "HOST:PORT")]
+    socks5: Option<String>,
+    /// Send requests through a local Tor daemon (127.0.0.1:9050).
+    #[arg(long, global = true)]
+    tor: bool,
+}
+
+impl Network {
+    /// Whether any route was given on the command line.
+    fn is_set(&self) -> bool {
+        self.proxy.is_some() || self.socks5.is_some() || self.tor
+    }
+
+    /// Route `builder` as requested; fails if more than one route was given.
+    ///
+    /// clap's group only catches conflicts on the same side of the subcommand, so this
+    /// checks again after global arguments have been merged.
+    fn apply(&self, builder: ClientBuilder) -> CliResult<ClientBuilder> {
+        let routes = [self.proxy.is_some(), self.socks5.is_some(), self.tor];
+        if routes.into_iter().filter(|set| *set).count() > 1 {
+            return Err("--proxy, --socks5, and --tor cannot be combined".into());
+        }
+        Ok(if let Some(proxy) = &self.proxy {
+            builder.proxy(proxy)
+        } else if let Some(addr) = &self.socks5 {
+            builder.proxy_config(ProxyConfig::socks5(addr))
+        } else if self.tor {
+            builder.tor()
+        } else {
+            builder
+        })
+    }
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Create an address and print it.
+    Create {
+        /// Local part of the address; random when omitted.
+        #[arg(value_parser = validate::alias)]
+        alias: Option<String>,
+        /// GuerrillaMail domain to create the address on.
+        #[arg(long)]
+        domain: Option<String>,
+    },
+    /// List the messages in an inbox.
+    List {
+        /// Address to list.
+        #[arg(value_parser = validate::address)]
+        address: String,
+    },
+    /// Print a message with its body.
+    Fetch {
+        /// Address the message was sent to.
+        #[arg(value_parser = validate::address)]
+        address: String,
+        /// Message ID, as shown by `list`.
+        mail_id: String,
+    },
+    /// Save every attachment of a message to disk.
+    Attachments {
+        /// Address the message was sent to.
+        #[arg(value_parser = validate::address)]
+        address: String,
+        /// Message ID, as shown by `list`.
+        mail_id: String,
+        /// Directory to save into; created if missing.
+        #[arg(long, default_value = ".")]
+        out_dir: PathBuf,
+    },
+    /// Download every message, bodies and attachments included, into a mail archive.
+    Export {
+        /// Address to export.
+        #[arg(value_parser = validate::address)]
+        address: String,
+        /// Archive format.
+        #[arg(long, value_enum, default_value_t = ArchiveFormat::Mbox)]
+        format: ArchiveFormat,
+        /// Output file for `mbox` (default `inbox.mbox`), directory for `eml` (default `.`).
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+    /// Forget an address.
+    Delete {
+        /// Address to forget.
+        #[arg(value_parser = validate::address)]
+        address: String,
+    },
+    /// Extend an inbox's lifetime and print its new expiry (Unix seconds).
+    Extend {
+        /// Address to extend.
+        #[arg(value_parser = validate::address)]
+        address: String,
+    },
+    /// Print new messages as they arrive.
+    Watch(Follow),
+    /// Print the full body of each new message as it arrives, like `tail -f`.
+    ///
+    /// Text output ends every message with a `--- end of message <id> ---` line; use
+    /// `--output jsonl` for one JSON object per message.
+    Tail(Follow),
+    /// Wait for the next message carrying a one-time code and print the code.
+    Otp {
+        /// Address the code will be sent to.
+        #[arg(value_parser = validate::address)]
+        address: String,
+        /// Regex the code must match; its first capture group is printed if it has one.
+        /// Defaults to a 4–8 digit number, preferring one that follows words like "code".
+        #[arg(long)]
+        pattern: Option<Regex>,
+        /// Seconds to wait before failing.
+        #[arg(long, default_value_t = 120)]
+        timeout: u64,
+        /// Seconds between inbox polls.
+        #[arg(long, default_value_t = 5)]
+        interval: u64,
+    },
+    /// Browse an inbox interactively.
+    #[cfg(feature = "tui")]
+    Ui {
+        /// Address to browse.
+        #[arg(value_parser = validate::address)]
+        address: String,
+        /// Seconds between automatic refreshes.
+        #[arg(long, default_value_t = 10)]
+        interval: u64,
+    },
+    /// Check DNS, TCP, TLS, the homepage bootstrap, and the API, printing a hint for failures.
+    Doctor,
+    /// Forget every address recorded by `create` and purge expired records.
+    #[command(visible_alias = "nuke")]
+    Cleanup,
+    /// Print a shell completion script, e.g. `guerrillamail completions bash > ...`.
+    Completions {
+        /// Shell to generate completions for.
+        shell: Shell,
+    },
+}
+
+type CliResult<T = ()> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(cli: Cli) -> CliResult {
+    let Cli {
+        output,
+        profile,
+        network,
+        command,
+    } = cli;
+    if let Command::Completions { shell } = command {
+        let mut cli = Cli::command();
+        clap_complete::generate(shell, &mut cli, "guerrillamail", &mut std::io::stdout());
+        return Ok(());
+    }
+    let config = Config::load(profile.as_deref())?;
+    let builder = || network.apply(config.apply(Client::builder()));
+    if let Command::Cleanup = command {
+        return cleanup(builder, output).await;
+    }
+    if let Command::Doctor = command {
+        let proxied = network.is_set() || config.proxy.is_some();
+        let report = doctor::run(builder()?, proxied).await?;
+        output.print_list(report.checks(), |check| {
+            row(check, &["check", "status", "detail", "hint"])
+                .trim_end()
+                .to_string()
+        });
+        if report.failed() {
+            return Err("some checks failed".into());
+        }
+        return Ok(());
+    }
+    let client = match config.saved_session()? {
+        Some(session) => builder()?.session(session),
+        None => builder()?,
+    };
+    let client = client.build().await?;
+    let result = run_command(&client, command, output).await;
+    config.save_session(&client.session())?;
+    result
+}
+
+async fn run_command(client: &Client, command: Command, output: Format) -> CliResult {
+    match command {
+        Command::Create { alias, domain } => {
+            let alias = alias.unwrap_or_else(|| AliasGenerator::default().generate());
+            let assigned = match domain {
+                Some(domain) => client.create_email_with_domain(&alias, &domain).await?,
+                None => client.create_email(&alias).await?,
+            };
+            if let Assigned::Modified { requested, actual } = &assigned {
+                eprintln!("warning: asked for alias {requested:?} but got {actual}");
+            }
+            let email = assigned.into_address();
+            registry::record(&email, &client.session())?;
+            output.print(&json!({ "email": email }), || email.clone());
+        }
+        Command::List { address } => {
+            let email = open(client, &address).await?;
+            let messages = client.get_messages(&email).await?;
+            let messages: Vec<_> = messages.iter().map(message_json).collect();
+            output.print_list(&messages, |message| {
+                row(message, &["mail_id", "mail_from", "mail_subject"])
+            });
+        }
+        Command::Fetch { address, mail_id } => {
+            let email = open(client, &address).await?;
+            let details = client.fetch_email(&email, &mail_id).await?;
+            output.print(&details_json(&details), || {
+                format!(
+                    "From: {}\nSubject: {}\nDate: {}\n\n{}",
+                    details.mail_from,
+                    details.mail_subject,
+                    details.mail_timestamp,
+                    details.mail_body
+                )
+            });
+        }
+        Command::Attachments {
+            address,
+            mail_id,
+            out_dir,
+        } => {
+            let email = open(client, &address).await?;
+            let attachments = client.list_attachments(&email, &mail_id).await?;
+            std::fs::create_dir_all(&out_dir)?;
+            let mut saved = Vec::new();
+            for attachment in &attachments {
+                let bytes = client
+                    .fetch_attachment(&email, &mail_id, attachment)
+                    .await?;
+                let fallback = format!("attachment-{}", attachment.part_id);
+                let name = files::sanitize_filename(&attachment.filename, &fallback);
+                let path = files::unique_path(&out_dir, &name);
+                std::fs::write(&path, &bytes)
+                    .map_err(|err| format!("writing {}: {err}", path.display()))?;
+                saved.push(json!({
+                    "filename": attachment.filename,
+                    "path": path,
+                    "bytes": bytes.len(),
+                }));
+            }
+            output.print_list(&saved, |file| row(file, &["path", "bytes"]));
+        }
+        Command::Export {
+            address,
+            format,
+            out,
+        } => {
+            let email = open(client, &address).await?;
+            let written = export(client, &email, format, out).await?;
+            output.print_list(&written, |file| row(file, &["path", "messages"]));
+        }
+        Command::Delete { address } => {
+            let email = open(client, &address).await?;
+            let deleted = client.delete_email(&email).await?;
+            registry::forget(&email)?;
+            output.print(&json!({ "email": email, "deleted": deleted }), String::new);
+        }
+        Command::Extend { address } => {
+            let email = open(client, &address).await?;
+            let expires_at = client.extend_session(&email).await?;
+            registry::touch(&email, &client.session())?;
+            output.print(&json!({ "email": email, "expires_at": expires_at }), || {
+                expires_at.to_string()
+            });
+        }
+        Command::Watch(follow) => watch(client, follow, output, false).await?,
+        Command::Tail(follow) => watch(client, follow, output, true).await?,
+        Command::Otp {
+            address,
+            pattern,
+            timeout,
+            interval,
+        } => {
+            let email = open(client, &address).await?;
+            let pattern = pattern.map_or(CodePattern::Digits, CodePattern::Regex);
+            let wait = wait_for_code(client, email, pattern, interval);
+            let Ok(found) = tokio::time::timeout(Duration::from_secs(timeout), wait).await else {
+                return Err(format!("no code arrived within {timeout}s").into());
+            };
+            let (code, details) = found?;
+            let result = json!({
+                "code": code,
+                "mail_id": details.mail_id,
+                "mail_from": details.mail_from,
+                "mail_subject": details.mail_subject,
+            });
+            output.print(&result, || code.clone());
+        }
+        #[cfg(feature = "tui")]
+        Command::Ui { address, interval } => {
+            let email = open(client, &address).await?;
+            ui::run(client, email, Duration::from_secs(interval)).await?;
+        }
+        Command::Cleanup | Command::Doctor | Command::Completions { .. } => {
+            unreachable!("handled before connecting")
+        }
+    }
+    Ok(())
+}
+
+/// Print messages arriving at the followed address until `--count` have been printed
+/// (forever without one), or `--timeout` passes. With `full`, each message is fetched and
+/// printed with its body.
+async fn watch(client: &Client, follow: Follow, output: Format, full: bool) -> CliResult {
+    let email = open(client, &follow.address).await?;
+    let count = follow.count;
+    let watch = follow_inbox(client, email, &follow, output, full);
+    let Some(secs) = follow.timeout else {
+        return watch.await;
+    };
+    match tokio::time::timeout(Duration::from_secs(secs), watch).await {
+        Ok(result) => result,
+        Err(_) if count.is_some() => Err("timed out waiting for messages".into()),
+        Err(_) => Ok(()),
+    }
+}
+
+async fn follow_inbox(
+    client: &Client,
+    email: String,
+    follow: &Follow,
+    output: Format,
+    full: bool,
+) -> CliResult {
+    let skip: HashSet<String> = if follow.existing {
+        HashSet::new()
+    } else {
+        let messages = client.get_messages(&email).await?;
+        messages
+            .into_iter()
+            .map(|message| message.mail_id)
+            .collect()
+    };
+    let interval = Duration::from_secs(follow.interval);
+    let watcher = MultiWatcher::new(client.clone()).poll_interval(interval);
+    watcher.watch(email.clone());
+    let mut events = watcher.start();
+    let mut printed = 0;
+    while follow.count.is_none_or(|count| printed < count) {
+        match events.next().await {
+            Some(WatchEvent::Message { message, .. }) if !skip.contains(&message.mail_id) => {
+                if full {
+                    let details = client.fetch_email(&email, &message.mail_id).await?;
+                    output.print(&details_json(&details), || {
+                        format!(
+                            "From: {}\nSubject: {}\nDate: {}\n\n{}\n--- end of message {} ---",
+                            details.mail_from,
+                            details.mail_subject,
+                            details.mail_timestamp,
+                            details.mail_body.trim_end(),
+                            details.mail_id
+                        )
+                    });
+                } else {
+                    let message = message_json(&message);
+                    output.print(&message, || {
+                        row(
+                            &message,
+                            &["mail_id", "mail_from", "mail_subject", "mail_excerpt"],
+                        )
+                    });
+                }
+                printed += 1;
+            }
+            Some(WatchEvent::Message { .. }) => {}
+            Some(WatchEvent::PollFailed { error, .. }) => {
+                eprintln!("warning: poll failed: {error}");
+            }
+            None => return Err("watcher stopped unexpectedly".into()),
+        }
+    }
+    Ok(())
+}
+
+/// Wait for a new message at `email` containing a code, returning the code and its message.
+///
+/// Messages already in the inbox are ignored, as are new ones without a code.
+async fn wait_for_code(
+    client: &Client,
+    email: String,
+    pattern: CodePattern,
+    interval: u64,
+) -> CliResult<(String, EmailDetails)> {
+    let messages = client.get_messages(&email).await?;
+    let skip: HashSet<String> = messages
+        .into_iter()
+        .map(|message| message.mail_id)
+        .collect();
+    let watcher = MultiWatcher::new(client.clone()).poll_interval(Duration::from_secs(interval));
+    watcher.watch(email.clone());
+    let mut events = watcher.start();
+    loop {
+        match events.next().await {
+            Some(WatchEvent::Message { message, .. }) if !skip.contains(&message.mail_id) => {
+                let details = client.fetch_email(&email, &message.mail_id).await?;
+                if let Some(code) = details.extract_code(pattern.clone()) {
+                    return Ok((code, details));
+                }
+            }
+            Some(WatchEvent::Message { .. }) => {}
+            Some(WatchEvent::PollFailed { error, .. }) => {
+                eprintln!("warning: poll failed: {error}");
+            }
+            None => return Err("watcher stopped unexpectedly".into()),
+        }
+    }
+}
+
+/// Write every message in `email` to `out` as `format`, returning what was written.
+async fn export(
+    client: &Client,
+    email: &str,
+    format: ArchiveFormat,
+    out: Option<PathBuf>,
+) -> CliResult<Vec<serde_json::Value>> {
+    let mut messages = Vec::new();
+    for message in client.get_messages(email).await? {
+        let details = client.fetch_email(email, &message.mail_id).await?;
+        let mut attachments = Vec::new();
+        for attachment in &details.attachments {
+            let bytes = client
+                .fetch_attachment(email, &details.mail_id, attachment)
+                .await?;
+            let content_type = attachment.content_type_or_hint.clone();
+            let content_type = content_type.unwrap_or_else(|| "application/octet-stream".into());
+            attachments.push((attachment.filename.clone(), content_type, bytes));
+        }
+        let eml = export::eml(email, &details, &attachments);
+        messages.push((details, eml));
+    }
+
+    let write = |path: &std::path::Path, contents: &str| {
+        std::fs::write(path, contents).map_err(|err| format!("writing {}: {err}", path.display()))
+    };
+    match format {
+        ArchiveFormat::Mbox => {
+            let path = out.unwrap_or_else(|| PathBuf::from("inbox.mbox"));
+            let mut mbox = String::new();
+            for (details, eml) in &messages {
+                export::append_mbox(&mut mbox, &details.mail_from, details, eml);
+            }
+            write(&path, &mbox)?;
+            Ok(vec![json!({ "path": path, "messages": messages.len() })])
+        }
+        ArchiveFormat::Eml => {
+            let dir = out.unwrap_or_else(|| PathBuf::from("."));
+            std::fs::create_dir_all(&dir)?;
+            let mut written = Vec::new();
+            for (details, eml) in &messages {
+                let name = files::sanitize_filename(&details.mail_id, "message");
+                let path = files::unique_path(&dir, &format!("{name}.eml"));
+                write(&path, eml)?;
+                written.push(json!({ "path": path, "messages": 1 }));
+            }
+            Ok(written)
+        }
+    }
+}
+
+/// Forget every recorded address with the session that created it, and drop stale records.
+///
+/// Fails after trying every address if any of them could not be forgotten; their records are
+/// kept for the next run.
+async fn cleanup(builder: impl Fn() -> CliResult<ClientBuilder>, output: Format) -> CliResult {
+    let mut results = Vec::new();
+    let mut failed = 0;
+    for entry in registry::entries()? {
+        let status = match &entry.session {
+            _ if entry.is_stale() => "expired".to_string(),
+            Err(_) => "unreadable".to_string(),
+            Ok(session) => {
+                let forgotten = match builder()?.session(session.clone()).build().await {
+                    Ok(client) => client.delete_email(&entry.email).await,
+                    Err(err) => Err(err),
+                };
+                match forgotten {
+                    Ok(_) => "deleted".to_string(),
+                    Err(err) => format!("failed: {err}"),
+                }
+            }
+        };
+        if status.starts_with("failed") {
+            failed += 1;
+        } else {
+            entry.remove()?;
+        }
+        results.push(json!({ "email": entry.email, "status": status }));
+    }
+    output.print_list(&results, |result| row(result, &["email", "status"]));
+    if failed > 0 {
+        return Err(format!("{failed} address(es) could not be deleted").into());
+    }
+    Ok(())
+}
+
+/// Attach this session to `address`, returning the address GuerrillaMail reports.
+///
+/// Fails if GuerrillaMail assigns a different alias, so commands never act on an inbox the user
+/// did not name.
+async fn open(client: &Client, address: &str) -> CliResult<String> {
+    let assigned = match address.split_once('@') {
+        Some((alias, domain)) => client.create_email_with_domain(alias, domain).await?,
+        None => client.create_email(address).await?,
+    };
+    match assigned {
+        Assigned::Exact(email) => Ok(email),
+        Assigned::Modified { requested, actual } => {
+            Err(format!("asked for alias {requested:?} but GuerrillaMail opened {actual}").into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cli_definition_is_valid() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn proxy_flags_are_global_and_exclusive() {
+        let cli = Cli::try_parse_from(["guerrillamail", "list", "qa", "--socks5", "10.0.0.1:1080"]);
+        assert_eq!(
+            cli.unwrap().network.socks5.as_deref(),
+            Some("10.0.0.1:1080")
+        );
+
+        let both = Cli::try_parse_from(["guerrillamail", "--tor", "list", "qa", "--proxy", "x"]);
+        assert!(both.unwrap().network.apply(Client::builder()).is_err());
+    }
+}
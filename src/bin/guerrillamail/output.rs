@@ -0,0 +1,106 @@
+//! Output formats shared by all subcommands.
+
+use clap::ValueEnum;
+use guerrillamail_client::{EmailDetails, Message};
+use serde_json::{Value, json};
+
+/// How results are written to stdout.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Human-readable text; lists are tab-separated rows.
+    #[default]
+    Table,
+    /// One pretty-printed JSON document per result.
+    Json,
+    /// One compact JSON object per line; lists print one line per item.
+    Jsonl,
+}
+
+impl Format {
+    /// Print a single result; `table` renders it as text and prints nothing when empty.
+    pub fn print(self, value: &Value, table: impl FnOnce() -> String) {
+        match self {
+            Format::Table => {
+                let text = table();
+                if !text.is_empty() {
+                    println!("{text}");
+                }
+            }
+            Format::Json => println!("{value:#}"),
+            Format::Jsonl => println!("{value}"),
+        }
+    }
+
+    /// Print a list of results; `row` renders one item as a line of text.
+    pub fn print_list(self, values: &[Value], row: impl Fn(&Value) -> String) {
+        match self {
+            Format::Table => values.iter().for_each(|value| println!("{}", row(value))),
+            Format::Json => println!("{:#}", Value::from(values)),
+            Format::Jsonl => values.iter().for_each(|value| println!("{value}")),
+        }
+    }
+}
+
+/// JSON form of a message header.
+pub fn message_json(message: &Message) -> Value {
+    json!({
+        "mail_id": message.mail_id,
+        "mail_from": message.mail_from,
+        "mail_subject": message.mail_subject,
+        "mail_excerpt": message.mail_excerpt,
+        "mail_timestamp": message.mail_timestamp,
+        "mail_read": message.mail_read,
+        "mail_size": message.mail_size,
+        "attachment_count": message.attachment_count,
+    })
+}
+
+/// JSON form of a full message.
+pub fn details_json(details: &EmailDetails) -> Value {
+    let attachments: Vec<_> = details
+        .attachments
+        .iter()
+        .map(|attachment| {
+            json!({
+                "filename": attachment.filename,
+                "content_type": attachment.content_type_or_hint,
+                "part_id": attachment.part_id,
+            })
+        })
+        .collect();
+    json!({
+        "mail_id": details.mail_id,
+        "mail_from": details.mail_from,
+        "mail_subject": details.mail_subject,
+        "mail_timestamp": details.mail_timestamp,
+        "mail_body": details.mail_body,
+        "attachments": attachments,
+    })
+}
+
+/// Tab-separated `fields` of `value`, for table rows.
+pub fn row(value: &Value, fields: &[&str]) -> String {
+    fields
+        .iter()
+        .map(|field| match &value[*field] {
+            Value::String(text) => text.clone(),
+            other => other.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\t")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rows_join_fields_with_tabs() {
+        let value = json!({ "mail_id": "7", "mail_subject": "Hi", "mail_read": false });
+
+        assert_eq!(
+            row(&value, &["mail_id", "mail_subject", "mail_read"]),
+            "7\tHi\tfalse"
+        );
+    }
+}
@@ -0,0 +1,114 @@
+//! Local record of the addresses `create` handed out, for `cleanup`.
+//!
+//! Each address gets one file in `$XDG_STATE_HOME/guerrillamail/sessions` (falling back to
+//! `~/.local/state`), named after the address and holding the session that owns it, written
+//! with [`Session::save`] so only the current user can read it. The file's modification time
+//! is when the inbox was last created or extended; once it is older than
+//! [`INBOX_LIFETIME`] GuerrillaMail has expired the inbox and the file is stale.
+
+use crate::files;
+use guerrillamail_client::{INBOX_LIFETIME, Session};
+use std::io;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+/// One recorded address.
+#[derive(Debug)]
+pub struct Entry {
+    /// The address, as returned by GuerrillaMail.
+    pub email: String,
+    /// Session that created the address.
+    pub session: io::Result<Session>,
+    /// Time since the inbox was created or last extended.
+    pub age: Duration,
+    path: PathBuf,
+}
+
+impl Entry {
+    /// Whether GuerrillaMail has already expired this inbox.
+    pub fn is_stale(&self) -> bool {
+        self.age >= INBOX_LIFETIME
+    }
+
+    /// Delete the record.
+    pub fn remove(&self) -> io::Result<()> {
+        std::fs::remove_file(&self.path)
+    }
+}
+
+/// Record that `session` owns `email`, resetting its age.
+pub fn record(email: &str, session: &Session) -> Result<(), String> {
+    let path = path(email).ok_or("cannot locate the state directory; set HOME")?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|err| format!("creating {}: {err}", dir.display()))?;
+    }
+    session
+        .save(&path)
+        .map_err(|err| format!("writing {}: {err}", path.display()))
+}
+
+/// Reset the age of `email`'s record, if it has one.
+pub fn touch(email: &str, session: &Session) -> Result<(), String> {
+    match path(email) {
+        Some(path) if path.exists() => record(email, session),
+        _ => Ok(()),
+    }
+}
+
+/// Forget `email`'s record, if it has one.
+pub fn forget(email: &str) -> io::Result<()> {
+    match path(email).map(std::fs::remove_file) {
+        Some(Err(err)) if err.kind() != io::ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
+
+/// Every recorded address, oldest first.
+pub fn entries() -> io::Result<Vec<Entry>> {
+    let Some(dir) = dir() else {
+        return Ok(Vec::new());
+    };
+    let read = match std::fs::read_dir(&dir) {
+        Ok(read) => read,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    let mut entries = Vec::new();
+    for file in read {
+        let path = file?.path();
+        let Some(email) = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_suffix(".json"))
+        else {
+            continue;
+        };
+        let modified = std::fs::metadata(&path)?.modified()?;
+        entries.push(Entry {
+            email: email.to_string(),
+            session: Session::load(&path).map_err(io::Error::other),
+            age: SystemTime::now()
+                .duration_since(modified)
+                .unwrap_or_default(),
+            path,
+        });
+    }
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.age));
+    Ok(entries)
+}
+
+fn dir() -> Option<PathBuf> {
+    let state_home = std::env::var_os("XDG_STATE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            let home = std::env::var_os("HOME")?;
+            Some(PathBuf::from(home).join(".local").join("state"))
+        })?;
+    Some(state_home.join("guerrillamail").join("sessions"))
+}
+
+fn path(email: &str) -> Option<PathBuf> {
+    let name = files::sanitize_filename(email, "address");
+    Some(dir()?.join(format!("{name}.json")))
+}
@@ -0,0 +1,198 @@
+//! Interactive two-pane inbox viewer (`guerrillamail ui`), behind the `tui` feature.
+//!
+//! Keys: `j`/`k` or arrows move, `Enter` opens, `r` refreshes, `d` deletes the selected
+//! message, `c` copies the open message's one-time code, `q` quits. The inbox also refreshes
+//! itself every `--interval` seconds.
+
+use crate::CliResult;
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD;
+use guerrillamail_client::{Client, CodePattern, EmailDetails, Message};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+/// How long each wait for a key lasts before checking the refresh timer.
+const TICK: Duration = Duration::from_millis(250);
+
+struct App {
+    email: String,
+    messages: Vec<Message>,
+    list: ListState,
+    open: Option<EmailDetails>,
+    status: String,
+}
+
+/// Run the viewer on `email` until the user quits.
+pub async fn run(client: &Client, email: String, interval: Duration) -> CliResult {
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, client, email, interval).await;
+    ratatui::restore();
+    result
+}
+
+async fn event_loop(
+    terminal: &mut DefaultTerminal,
+    client: &Client,
+    email: String,
+    interval: Duration,
+) -> CliResult {
+    let mut app = App {
+        email,
+        messages: Vec::new(),
+        list: ListState::default(),
+        open: None,
+        status: String::new(),
+    };
+    app.refresh(client).await;
+    let mut refreshed = Instant::now();
+
+    loop {
+        terminal.draw(|frame| app.draw(frame))?;
+        if refreshed.elapsed() >= interval {
+            app.refresh(client).await;
+            refreshed = Instant::now();
+        }
+        if !tokio::task::block_in_place(|| event::poll(TICK))? {
+            continue;
+        }
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Char('j') | KeyCode::Down => app.list.select_next(),
+            KeyCode::Char('k') | KeyCode::Up => app.list.select_previous(),
+            KeyCode::Enter | KeyCode::Char('o') => app.open_selected(client).await,
+            KeyCode::Char('r') => {
+                app.refresh(client).await;
+                refreshed = Instant::now();
+            }
+            KeyCode::Char('d') => app.delete_selected(client).await,
+            KeyCode::Char('c') => app.copy_code(),
+            _ => {}
+        }
+    }
+}
+
+impl App {
+    fn selected(&self) -> Option<&Message> {
+        self.messages.get(self.list.selected()?)
+    }
+
+    async fn refresh(&mut self, client: &Client) {
+        match client.get_messages(&self.email).await {
+            Ok(messages) => {
+                self.status = format!("{} message(s)", messages.len());
+                self.messages = messages;
+                if self.list.selected().is_none() && !self.messages.is_empty() {
+                    self.list.select(Some(0));
+                }
+            }
+            Err(err) => self.status = format!("refresh failed: {err}"),
+        }
+    }
+
+    async fn open_selected(&mut self, client: &Client) {
+        let Some(mail_id) = self.selected().map(|message| message.mail_id.clone()) else {
+            return;
+        };
+        match client.fetch_email(&self.email, &mail_id).await {
+            Ok(details) => self.open = Some(details),
+            Err(err) => self.status = format!("open failed: {err}"),
+        }
+    }
+
+    async fn delete_selected(&mut self, client: &Client) {
+        let Some(mail_id) = self.selected().map(|message| message.mail_id.clone()) else {
+            return;
+        };
+        match client.delete_messages(&self.email, &[&mail_id]).await {
+            Ok(_) => {
+                self.messages.retain(|message| message.mail_id != mail_id);
+                if self
+                    .open
+                    .as_ref()
+                    .is_some_and(|open| open.mail_id == mail_id)
+                {
+                    self.open = None;
+                }
+                self.status = format!("deleted {mail_id}");
+            }
+            Err(err) => self.status = format!("delete failed: {err}"),
+        }
+    }
+
+    /// Copy the open message's code with an OSC 52 escape, which most terminals (including
+    /// over SSH and tmux) forward to the system clipboard.
+    fn copy_code(&mut self) {
+        let Some(open) = &self.open else {
+            self.status = "open a message first".to_string();
+            return;
+        };
+        let Some(code) = open.extract_code(CodePattern::Digits) else {
+            self.status = "no code found in this message".to_string();
+            return;
+        };
+        let mut stdout = std::io::stdout();
+        let sequence = format!("\x1b]52;c;{}\x07", STANDARD.encode(&code));
+        self.status = match stdout
+            .write_all(sequence.as_bytes())
+            .and_then(|()| stdout.flush())
+        {
+            Ok(()) => format!("copied {code}"),
+            Err(err) => format!("copy failed: {err}"),
+        };
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, status] =
+            Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+        let [inbox, message] =
+            Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)])
+                .areas(main);
+
+        let items: Vec<ListItem> = self
+            .messages
+            .iter()
+            .map(|message| {
+                let style = if message.mail_read {
+                    Style::default()
+                } else {
+                    Style::default().add_modifier(Modifier::BOLD)
+                };
+                ListItem::new(format!("{}  {}", message.mail_from, message.mail_subject))
+                    .style(style)
+            })
+            .collect();
+        let list = List::new(items)
+            .block(Block::bordered().title(self.email.as_str()))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, inbox, &mut self.list);
+
+        let text = match &self.open {
+            Some(open) => format!(
+                "From: {}\nSubject: {}\n\n{}",
+                open.mail_from,
+                open.mail_subject,
+                open.body_text()
+            ),
+            None => "Press Enter to open the selected message.".to_string(),
+        };
+        let body = Paragraph::new(text)
+            .block(Block::bordered().title("Message"))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(body, message);
+
+        let help = "j/k move  enter open  r refresh  d delete  c copy code  q quit";
+        frame.render_widget(Line::from(format!("{}  |  {help}", self.status)), status);
+    }
+}
@@ -0,0 +1,48 @@
+//! Argument checks that run before any request is made.
+
+/// Check that `alias` can be used as a GuerrillaMail local part.
+pub fn alias(alias: &str) -> Result<String, String> {
+    match guerrillamail_client::alias::validate(alias) {
+        Ok(()) => Ok(alias.to_string()),
+        Err(guerrillamail_client::Error::InvalidAlias { reason, .. }) => Err(reason),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+/// Check an address argument: a bare alias, or `alias@domain`.
+pub fn address(address: &str) -> Result<String, String> {
+    match address.split_once('@') {
+        Some((local, domain)) => {
+            alias(local)?;
+            if domain.is_empty() || domain.contains(['@', ' ']) || !domain.contains('.') {
+                return Err(format!(
+                    "{domain:?} is not a domain; expected e.g. sharklasers.com"
+                ));
+            }
+        }
+        None => {
+            alias(address)?;
+        }
+    }
+    Ok(address.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_aliases_before_they_reach_the_api() {
+        assert!(alias("signup.test-1+ci").is_ok());
+        assert!(address("signup@sharklasers.com").is_ok());
+
+        assert!(alias("").is_err());
+        assert!(alias(&"a".repeat(65)).is_err());
+        assert!(alias(".hidden").is_err());
+        assert!(alias("a..b").is_err());
+        assert!(alias("two words").unwrap_err().contains("' '"));
+        assert!(address("user@").is_err());
+        assert!(address("user@localhost").is_err());
+        assert!(address("bad alias@sharklasers.com").is_err());
+    }
+}
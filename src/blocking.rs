@@ -0,0 +1,213 @@
+//! Blocking wrapper around the async [`Client`](crate::Client).
+//!
+//! For scripts and build tooling that do not run a Tokio runtime. Each [`Client`] owns a
+//! single-threaded runtime and drives the async client on it, so every setting of
+//! [`ClientBuilder`] applies unchanged. Only available with the
+//! `blocking` cargo feature.
+//!
+//! Calling these methods from inside an async runtime panics, as with any `block_on`; use the
+//! async client there instead.
+//!
+//! # Example
+//! ```no_run
+//! use guerrillamail_client::blocking::Client;
+//!
+//! fn main() -> Result<(), guerrillamail_client::Error> {
+//!     let client = Client::new()?;
+//!     let email = client.create_email("build-bot")?;
+//!     for message in client.get_messages(&email)? {
+//!         println!("{}: {}", message.mail_from, message.mail_subject);
+//!     }
+//!     client.delete_email(&email)?;
+//!     Ok(())
+//! }
+//! ```
+
+use crate::{
+    Assigned, Attachment, ClientBuilder, CurrentAddress, EmailDetails, InboxSnapshot, Message,
+    Result, Session,
+};
+use std::future::Future;
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+
+/// Blocking GuerrillaMail client; see the [module documentation](self).
+///
+/// Cheap to clone: clones share the underlying client and runtime.
+#[derive(Debug, Clone)]
+pub struct Client {
+    inner: crate::Client,
+    runtime: Arc<Runtime>,
+}
+
+impl Client {
+    /// Create a client with default settings; see [`crate::Client::new`].
+    ///
+    /// # Errors
+    /// Returns `Error::Io` if the runtime cannot be started, otherwise the errors of
+    /// [`crate::Client::new`].
+    pub fn new() -> Result<Self> {
+        Self::from_builder(crate::Client::builder())
+    }
+
+    /// Build a client from an async [`ClientBuilder`], blocking until bootstrap completes.
+    ///
+    /// # Errors
+    /// Returns `Error::Io` if the runtime cannot be started, otherwise the errors of
+    /// [`ClientBuilder::build`].
+    pub fn from_builder(builder: ClientBuilder) -> Result<Self> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        let inner = runtime.block_on(builder.build())?;
+        Ok(Self {
+            inner,
+            runtime: Arc::new(runtime),
+        })
+    }
+
+    /// The async client this wraps, for features without a blocking counterpart.
+    ///
+    /// Its futures must be driven by [`block_on`](Self::block_on), not by another runtime.
+    pub fn as_async(&self) -> &crate::Client {
+        &self.inner
+    }
+
+    /// Run `future` to completion on this client's runtime.
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+
+    /// See [`crate::Client::session`].
+    pub fn session(&self) -> Session {
+        self.inner.session()
+    }
+
+    /// See [`crate::Client::domains`].
+    pub fn domains(&self) -> Result<Vec<String>> {
+        self.block_on(self.inner.domains())
+    }
+
+    /// See [`crate::Client::create_email`].
+    pub fn create_email(&self, alias: &str) -> Result<Assigned> {
+        self.block_on(self.inner.create_email(alias))
+    }
+
+    /// See [`crate::Client::create_email_with_domain`].
+    pub fn create_email_with_domain(&self, alias: &str, domain: &str) -> Result<Assigned> {
+        self.block_on(self.inner.create_email_with_domain(alias, domain))
+    }
+
+    /// See [`crate::Client::create_random_email`].
+    pub fn create_random_email(&self) -> Result<(String, String)> {
+        self.block_on(self.inner.create_random_email())
+    }
+
+    /// See [`crate::Client::current_address`].
+    pub fn current_address(&self) -> Result<CurrentAddress> {
+        self.block_on(self.inner.current_address())
+    }
+
+    /// See [`crate::Client::get_messages`].
+    pub fn get_messages(&self, email: &str) -> Result<Vec<Message>> {
+        self.block_on(self.inner.get_messages(email))
+    }
+
+    /// See [`crate::Client::get_inbox`].
+    pub fn get_inbox(&self, email: &str) -> Result<InboxSnapshot> {
+        self.block_on(self.inner.get_inbox(email))
+    }
+
+    /// See [`crate::Client::get_unread_messages`].
+    pub fn get_unread_messages(&self, email: &str) -> Result<Vec<Message>> {
+        self.block_on(self.inner.get_unread_messages(email))
+    }
+
+    /// See [`crate::Client::search`].
+    pub fn search(&self, email: &str, query: crate::Query) -> Result<Vec<crate::SearchHit>> {
+        self.block_on(self.inner.search(email, query))
+    }
+
+    /// See [`crate::Client::fetch_email`].
+    pub fn fetch_email(&self, email: &str, mail_id: &str) -> Result<EmailDetails> {
+        self.block_on(self.inner.fetch_email(email, mail_id))
+    }
+
+    /// See [`crate::Client::fetch_attachment`].
+    pub fn fetch_attachment(
+        &self,
+        email: &str,
+        mail_id: &str,
+        attachment: &Attachment,
+    ) -> Result<Vec<u8>> {
+        self.block_on(self.inner.fetch_attachment(email, mail_id, attachment))
+    }
+
+    /// See [`crate::Client::click_verification_link`].
+    pub fn click_verification_link(
+        &self,
+        details: &EmailDetails,
+        selector: crate::LinkSelector,
+    ) -> Result<crate::LinkVisit> {
+        self.block_on(self.inner.click_verification_link(details, selector))
+    }
+
+    /// See [`crate::Client::delete_email`].
+    pub fn delete_email(&self, email: &str) -> Result<bool> {
+        self.block_on(self.inner.delete_email(email))
+    }
+
+    /// See [`crate::Client::delete_messages`].
+    pub fn delete_messages(&self, email: &str, mail_ids: &[&str]) -> Result<Vec<String>> {
+        self.block_on(self.inner.delete_messages(email, mail_ids))
+    }
+
+    /// See [`crate::Client::extend_session`].
+    pub fn extend_session(&self, email: &str) -> Result<u64> {
+        self.block_on(self.inner.extend_session(email))
+    }
+}
+
+impl ClientBuilder {
+    /// Build a [`blocking::Client`](Client); see [`Client::from_builder`].
+    ///
+    /// # Errors
+    /// Same as [`Client::from_builder`].
+    pub fn build_blocking(self) -> Result<Client> {
+        Client::from_builder(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::session_client;
+    use httpmock::Method::{GET, POST};
+    use httpmock::MockServer;
+    use serde_json::json;
+
+    #[test]
+    fn blocking_client_runs_without_an_outer_runtime() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(POST)
+                .path("/ajax.php")
+                .query_param("f", "set_email_user");
+            then.status(200)
+                .json_body(json!({ "email_addr": "script@sharklasers.com" }));
+        });
+        let inbox = server.mock(|when, then| {
+            when.method(GET)
+                .path("/ajax.php")
+                .query_param("f", "check_email");
+            then.status(200).json_body(json!({ "list": [] }));
+        });
+
+        let client = session_client(&server.base_url()).build_blocking().unwrap();
+        let email = client.create_email("script").unwrap();
+        let messages = client.get_messages(&email).unwrap();
+
+        assert_eq!(email, "script@sharklasers.com");
+        assert!(messages.is_empty());
+        inbox.assert();
+    }
+}
@@ -0,0 +1,320 @@
+//! Record/replay cassettes for offline tests.
+
+use crate::Result;
+use crate::middleware::request_pairs;
+use crate::transport::{Transport, TransportFuture, buffer};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// A [`Transport`] that records exchanges to a fixture file, or replays them without a network.
+///
+/// Record once against the real service with [`Cassette::record`], [`save`](Cassette::save) the
+/// file, and commit it; tests then build their client with [`Cassette::replay`] and run offline,
+/// including the client's bootstrap. Pass a clone to [`ClientBuilder::transport`]; clones share
+/// the same recording.
+///
+/// Requests are matched on method, path, and parameters (query and form), ignoring the `_`
+/// cache-buster and token-like parameters, so a replay does not depend on the session that was
+/// recorded. Identical requests are answered with successive recordings in order, and the last
+/// one repeats once they run out, which keeps polling loops working. A request with no
+/// recording gets a `404` whose body names the request, surfacing as `Error::Api`.
+///
+/// Cassette files store response bodies verbatim, including the API token handed out during
+/// bootstrap; GuerrillaMail tokens are short-lived, but review a cassette before publishing it.
+/// Only available with the `vcr` cargo feature.
+///
+/// [`ClientBuilder::transport`]: crate::ClientBuilder::transport
+///
+/// # Example
+/// ```no_run
+/// use guerrillamail_client::{Cassette, Client};
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), guerrillamail_client::Error> {
+/// // Once, with network access:
+/// let cassette = Cassette::record(reqwest::Client::new());
+/// let client = Client::builder().transport(cassette.clone()).build().await?;
+/// client.create_email("fixture").await?;
+/// cassette.save("tests/fixtures/create_email.json")?;
+///
+/// // In CI:
+/// let cassette = Cassette::replay("tests/fixtures/create_email.json")?;
+/// let client = Client::builder().transport(cassette).build().await?;
+/// client.create_email("fixture").await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Cassette {
+    mode: Arc<Mode>,
+    interactions: Arc<Mutex<Vec<Interaction>>>,
+}
+
+enum Mode {
+    Record(Box<dyn Transport>),
+    /// Replay; `used[i]` marks interactions already served.
+    Replay(Mutex<Vec<bool>>),
+}
+
+impl std::fmt::Debug for Cassette {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mode = match *self.mode {
+            Mode::Record(_) => "record",
+            Mode::Replay(_) => "replay",
+        };
+        f.debug_struct("Cassette")
+            .field("mode", &mode)
+            .field("interactions", &self.len())
+            .finish()
+    }
+}
+
+/// On-disk cassette document.
+#[derive(Serialize, Deserialize)]
+struct CassetteFile {
+    interactions: Vec<Interaction>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Interaction {
+    request: RequestKey,
+    response: RecordedResponse,
+}
+
+/// The parts of a request used for matching.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct RequestKey {
+    method: String,
+    path: String,
+    params: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecordedResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    /// Body when it is valid UTF-8.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body: Option<String>,
+    /// Body otherwise, e.g. a binary attachment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body_bytes: Option<Vec<u8>>,
+}
+
+impl Cassette {
+    /// Record every exchange sent through `inner`, typically a `reqwest::Client`.
+    pub fn record(inner: impl Transport + 'static) -> Self {
+        Self {
+            mode: Arc::new(Mode::Record(Box::new(inner))),
+            interactions: Arc::default(),
+        }
+    }
+
+    /// Serve the exchanges saved at `path` without touching the network. This is a blocking
+    /// call.
+    ///
+    /// # Errors
+    /// - Returns `Error::Io` if the file cannot be read.
+    /// - Returns `Error::Json` if the file is not a valid cassette.
+    pub fn replay(path: impl AsRef<Path>) -> Result<Self> {
+        let file: CassetteFile = serde_json::from_slice(&fs::read(path)?)?;
+        Ok(Self {
+            mode: Arc::new(Mode::Replay(Mutex::new(vec![
+                false;
+                file.interactions.len()
+            ]))),
+            interactions: Arc::new(Mutex::new(file.interactions)),
+        })
+    }
+
+    /// Write the recorded exchanges to `path` as JSON, replacing any existing file. This is a
+    /// blocking call.
+    ///
+    /// # Errors
+    /// - Returns `Error::Io` if the file cannot be written.
+    /// - Returns `Error::Json` if serialization fails.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let file = CassetteFile {
+            interactions: self.lock().clone(),
+        };
+        fs::write(path, serde_json::to_vec_pretty(&file)?)?;
+        Ok(())
+    }
+
+    /// Number of recorded (or loaded) exchanges.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Whether the cassette holds no exchanges.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Interaction>> {
+        self.interactions.lock().expect("cassette lock poisoned")
+    }
+
+    /// Answer `key` from the loaded interactions.
+    fn lookup(&self, key: &RequestKey, used: &Mutex<Vec<bool>>) -> RecordedResponse {
+        let interactions = self.lock();
+        let mut used = used.lock().expect("cassette lock poisoned");
+        let matches: Vec<usize> = (0..interactions.len())
+            .filter(|&i| interactions[i].request == *key)
+            .collect();
+        match matches.iter().find(|&&i| !used[i]).or(matches.last()) {
+            Some(&i) => {
+                used[i] = true;
+                interactions[i].response.clone()
+            }
+            None => RecordedResponse {
+                status: 404,
+                headers: Vec::new(),
+                body: Some(format!("no recorded response for {key}")),
+                body_bytes: None,
+            },
+        }
+    }
+}
+
+impl Transport for Cassette {
+    fn send(&self, request: reqwest::Request) -> TransportFuture<'_> {
+        Box::pin(async move {
+            let key = RequestKey::new(&request);
+            match &*self.mode {
+                Mode::Record(inner) => {
+                    let response = buffer(inner.send(request).await?).await?;
+                    let recorded = RecordedResponse::new(&response);
+                    self.lock().push(Interaction {
+                        request: key,
+                        response: recorded,
+                    });
+                    Ok(response.into())
+                }
+                Mode::Replay(used) => Ok(self.lookup(&key, used).into_response()?.into()),
+            }
+        })
+    }
+}
+
+impl RequestKey {
+    fn new(request: &reqwest::Request) -> Self {
+        let mut params = request_pairs(request);
+        params.retain(|(key, _)| key != "_" && !key.to_ascii_lowercase().contains("token"));
+        Self {
+            method: request.method().to_string(),
+            path: request.url().path().to_string(),
+            params,
+        }
+    }
+}
+
+impl std::fmt::Display for RequestKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.method, self.path)?;
+        for (i, (key, value)) in self.params.iter().enumerate() {
+            write!(f, "{}{key}={value}", if i == 0 { '?' } else { '&' })?;
+        }
+        Ok(())
+    }
+}
+
+impl RecordedResponse {
+    fn new(response: &http::Response<Vec<u8>>) -> Self {
+        let (body, body_bytes) = match String::from_utf8(response.body().clone()) {
+            Ok(body) => (Some(body), None),
+            Err(err) => (None, Some(err.into_bytes())),
+        };
+        Self {
+            status: response.status().as_u16(),
+            headers: response
+                .headers()
+                .iter()
+                .filter_map(|(name, value)| {
+                    Some((name.to_string(), value.to_str().ok()?.to_string()))
+                })
+                .collect(),
+            body,
+            body_bytes,
+        }
+    }
+
+    fn into_response(self) -> Result<http::Response<Vec<u8>>> {
+        let mut response = http::Response::builder().status(self.status);
+        for (name, value) in &self.headers {
+            response = response.header(name, value);
+        }
+        let body = self
+            .body
+            .map(String::into_bytes)
+            .or(self.body_bytes)
+            .unwrap_or_default();
+        response
+            .body(body)
+            .map_err(|_| crate::Error::response_parse("invalid response in cassette"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Client;
+    use httpmock::Method::GET;
+    use httpmock::MockServer;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn replays_a_recorded_session_offline() {
+        let server = MockServer::start();
+        let bootstrap = server.mock(|when, then| {
+            when.method(GET)
+                .path("/ajax.php")
+                .query_param("f", "get_email_address");
+            then.status(200)
+                .json_body(json!({ "sid_token": "recorded-sid" }));
+        });
+        let inbox = server.mock(|when, then| {
+            when.method(GET)
+                .path("/ajax.php")
+                .query_param("f", "check_email");
+            then.status(200).json_body(json!({ "list": [] }));
+        });
+        let path = std::env::temp_dir().join(format!(
+            "guerrillamail-cassette-{}-{}.json",
+            std::process::id(),
+            rand::random::<u32>()
+        ));
+
+        let cassette = Cassette::record(reqwest::Client::new());
+        let client = Client::builder()
+            .transport(cassette.clone())
+            .base_url(server.base_url())
+            .ajax_url(server.url("/ajax.php"))
+            .build()
+            .await
+            .unwrap();
+        client.get_messages("alias@example.com").await.unwrap();
+        cassette.save(&path).unwrap();
+        let recorded = cassette.len();
+
+        let cassette = Cassette::replay(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        let client = Client::builder()
+            .transport(cassette.clone())
+            .base_url(server.base_url())
+            .ajax_url(server.url("/ajax.php"))
+            .build()
+            .await
+            .unwrap();
+        let messages = client.get_messages("alias@example.com").await.unwrap();
+
+        assert!(messages.is_empty());
+        assert_eq!(cassette.len(), recorded);
+        bootstrap.assert_hits(1);
+        inbox.assert_hits(1);
+        let missing = client.get_messages("other@example.com").await.unwrap_err();
+        assert!(matches!(missing, crate::Error::Api { status, .. } if status == 404));
+    }
+}
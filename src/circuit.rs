@@ -0,0 +1,198 @@
+//! Circuit breaker that stops traffic to GuerrillaMail after repeated upstream failures.
+
+use crate::{Error, Result};
+use std::sync::Mutex;
+use std::time::Duration;
+use web_time::Instant;
+
+/// Counts consecutive upstream failures and fails fast while the circuit is open.
+///
+/// After `threshold` consecutive failures the circuit opens for `cool_down`; calls made in that
+/// window return `Error::CircuitOpen` without touching the network. Once the cool-down has passed
+/// the next call is let through as a probe: success closes the circuit, failure re-opens it.
+/// Other calls keep failing fast while the probe is in flight; a probe that is dropped before
+/// finishing lets the next call probe instead.
+#[derive(Debug)]
+pub(crate) struct CircuitBreaker {
+    threshold: u32,
+    cool_down: Duration,
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+    /// Whether a probe is in flight after the cool-down (half-open).
+    probing: bool,
+}
+
+/// A call let through by [`CircuitBreaker::check`], to be reported with
+/// [`record`](Admission::record).
+#[derive(Debug)]
+pub(crate) struct Admission<'a> {
+    breaker: &'a CircuitBreaker,
+    probe: bool,
+}
+
+impl CircuitBreaker {
+    /// # Panics
+    /// Panics if `threshold` is zero.
+    pub(crate) fn new(threshold: u32, cool_down: Duration) -> Self {
+        assert!(threshold > 0, "circuit breaker threshold must be non-zero");
+        Self {
+            threshold,
+            cool_down,
+            state: Mutex::default(),
+        }
+    }
+
+    /// Fail fast with `Error::CircuitOpen` while the circuit is open or another call is probing.
+    pub(crate) fn check(&self) -> Result<Admission<'_>> {
+        let mut state = self.state.lock().expect("circuit breaker lock poisoned");
+        let Some(until) = state.open_until else {
+            return Ok(Admission {
+                breaker: self,
+                probe: false,
+            });
+        };
+        let now = Instant::now();
+        if until > now {
+            return Err(Error::CircuitOpen {
+                retry_in: until - now,
+            });
+        }
+        if state.probing {
+            return Err(Error::CircuitOpen {
+                retry_in: Duration::ZERO,
+            });
+        }
+        state.probing = true;
+        Ok(Admission {
+            breaker: self,
+            probe: true,
+        })
+    }
+}
+
+impl Admission<'_> {
+    /// Record the outcome of the admitted call.
+    pub(crate) fn record<T>(self, result: &Result<T>) {
+        let breaker = self.breaker;
+        let mut state = breaker.state.lock().expect("circuit breaker lock poisoned");
+        match result {
+            Err(err) if is_upstream_failure(err) => {
+                state.consecutive_failures = state.consecutive_failures.saturating_add(1);
+                if state.consecutive_failures >= breaker.threshold {
+                    state.open_until = Some(Instant::now() + breaker.cool_down);
+                }
+            }
+            _ => *state = State::default(),
+        }
+    }
+}
+
+impl Drop for Admission<'_> {
+    fn drop(&mut self) {
+        if self.probe {
+            self.breaker
+                .state
+                .lock()
+                .expect("circuit breaker lock poisoned")
+                .probing = false;
+        }
+    }
+}
+
+/// Failures that indicate GuerrillaMail (or the path to it) is unhealthy, as opposed to a bad
+/// request from the caller.
+fn is_upstream_failure(err: &Error) -> bool {
+    match err {
+        Error::Request { .. } | Error::RateLimited { .. } | Error::ChallengeDetected { .. } => true,
+        Error::Api { status, .. } => status.is_server_error(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server_error() -> Result<()> {
+        Err(Error::Api {
+            status: reqwest::StatusCode::BAD_GATEWAY,
+            headers: Default::default(),
+            body: String::new(),
+            request_id: None,
+        })
+    }
+
+    #[test]
+    fn opens_after_threshold_and_closes_after_successful_probe() {
+        let breaker = CircuitBreaker::new(2, Duration::from_millis(20));
+
+        breaker.check().unwrap().record(&server_error());
+        breaker.check().unwrap().record(&server_error());
+        assert!(matches!(breaker.check(), Err(Error::CircuitOpen { .. })));
+
+        std::thread::sleep(Duration::from_millis(30));
+        breaker.check().unwrap().record(&Ok(()));
+        breaker.check().unwrap().record(&server_error());
+        assert!(breaker.check().is_ok());
+    }
+
+    #[test]
+    fn failed_probe_reopens_immediately() {
+        let breaker = CircuitBreaker::new(1, Duration::from_millis(20));
+
+        breaker.check().unwrap().record(&server_error());
+        std::thread::sleep(Duration::from_millis(30));
+        breaker.check().unwrap().record(&server_error());
+
+        assert!(matches!(breaker.check(), Err(Error::CircuitOpen { .. })));
+    }
+
+    #[test]
+    fn half_open_admits_a_single_probe() {
+        let breaker = CircuitBreaker::new(1, Duration::from_millis(20));
+        breaker.check().unwrap().record(&server_error());
+        std::thread::sleep(Duration::from_millis(30));
+
+        let barrier = std::sync::Barrier::new(8);
+        let admitted: Vec<_> = std::thread::scope(|scope| {
+            let callers: Vec<_> = (0..8)
+                .map(|_| {
+                    scope.spawn(|| {
+                        barrier.wait();
+                        breaker.check().ok()
+                    })
+                })
+                .collect();
+            callers
+                .into_iter()
+                .filter_map(|caller| caller.join().unwrap())
+                .collect()
+        });
+        assert_eq!(admitted.len(), 1);
+        assert!(matches!(breaker.check(), Err(Error::CircuitOpen { .. })));
+
+        // A probe dropped without an outcome hands the slot to the next caller.
+        drop(admitted);
+        let probe = breaker.check().unwrap();
+        assert!(breaker.check().is_err());
+        probe.record(&Ok(()));
+        assert!(breaker.check().is_ok());
+    }
+
+    #[test]
+    fn client_errors_do_not_count() {
+        let breaker = CircuitBreaker::new(1, Duration::from_secs(60));
+
+        breaker
+            .check()
+            .unwrap()
+            .record::<()>(&Err(Error::response_parse("bad shape")));
+
+        assert!(breaker.check().is_ok());
+    }
+}
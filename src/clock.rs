@@ -0,0 +1,98 @@
+//! Injectable wall clock.
+
+use std::time::Duration;
+use web_time::{SystemTime, UNIX_EPOCH};
+
+/// Source of the current time for a [`Client`], for [`ClientBuilder::clock`].
+///
+/// The client reads the clock for cache-busting `_` parameters and for inbox bookkeeping:
+/// [`Inbox::created_at`], [`Inbox::extended_at`], the keep-alive schedule, and
+/// [`InboxPool`] staleness. Swap it to get deterministic timestamps in tests.
+///
+/// [`Client`]: crate::Client
+/// [`ClientBuilder::clock`]: crate::ClientBuilder::clock
+/// [`Inbox::created_at`]: crate::Inbox::created_at
+/// [`Inbox::extended_at`]: crate::Inbox::extended_at
+/// [`InboxPool`]: crate::InboxPool
+pub trait Clock: Send + Sync {
+    /// The current wall-clock time.
+    fn now(&self) -> SystemTime;
+}
+
+/// The operating system's clock (the default).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// A clock driven by Tokio's timer, so `tokio::time::pause` and `advance` move it.
+///
+/// Reports a fixed starting time plus the Tokio time elapsed since the clock was created. In a
+/// runtime with paused time this makes expiry handling fully deterministic; otherwise it tracks
+/// real time.
+///
+/// # Example
+/// ```
+/// use guerrillamail_client::{Clock, TokioClock};
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// # #[tokio::main(flavor = "current_thread", start_paused = true)]
+/// # async fn main() {
+/// let clock = TokioClock::starting_at(UNIX_EPOCH + Duration::from_secs(1_700_000_000));
+/// tokio::time::advance(Duration::from_secs(60)).await;
+/// assert_eq!(clock.now(), UNIX_EPOCH + Duration::from_secs(1_700_000_060));
+/// # }
+/// ```
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone, Copy)]
+pub struct TokioClock {
+    start: SystemTime,
+    anchor: tokio::time::Instant,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl TokioClock {
+    /// A clock starting at the current system time.
+    pub fn new() -> Self {
+        Self::starting_at(SystemTime::now())
+    }
+
+    /// A clock starting at `start`.
+    pub fn starting_at(start: SystemTime) -> Self {
+        Self {
+            start,
+            anchor: tokio::time::Instant::now(),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Default for TokioClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Clock for TokioClock {
+    fn now(&self) -> SystemTime {
+        self.start + self.anchor.elapsed()
+    }
+}
+
+/// Time since the Unix epoch according to `clock`.
+///
+/// # Panics
+///
+/// Panics if the clock is before the Unix epoch. This indicates a misconfigured or broken
+/// clock and is treated as a fatal error.
+pub(crate) fn since_epoch(clock: &dyn Clock) -> Duration {
+    clock
+        .now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system clock is before UNIX_EPOCH")
+}
@@ -4,6 +4,8 @@
 //! GuerrillaMail service, including network failures, parsing issues during
 //! client bootstrap, and malformed API responses.
 
+use crate::Message;
+use std::fmt;
 use thiserror::Error;
 
 /// Errors that can occur during GuerrillaMail operations.
@@ -15,26 +17,129 @@ use thiserror::Error;
 pub enum Error {
     /// An HTTP request failed.
     ///
-    /// This includes network connectivity issues, TLS errors,
-    /// timeouts, and non-success HTTP status codes returned
-    /// by the GuerrillaMail service.
-    #[error("HTTP request failed: {0}")]
-    Request(#[from] reqwest::Error),
+    /// This includes network connectivity issues, TLS errors, and
+    /// timeouts. Non-success HTTP statuses are reported as `Api`.
+    #[error("HTTP request failed: {source}")]
+    Request {
+        /// The underlying reqwest error.
+        #[source]
+        source: reqwest::Error,
+        /// ID of the failed request; see [`Error::request_id`]. `None` when the error did not
+        /// come from an exchange (building the request, say).
+        request_id: Option<String>,
+    },
+
+    /// GuerrillaMail answered with a non-success HTTP status.
+    ///
+    /// Carries the status, a small set of diagnostic headers (content type, `Retry-After`,
+    /// server/CDN identifiers), the start of the response body, which often contains a
+    /// human-readable error message, and the ID of the request that failed.
+    #[error("GuerrillaMail returned HTTP {status}: {body}")]
+    Api {
+        /// HTTP status code of the response.
+        status: reqwest::StatusCode,
+        /// Diagnostic response headers; cookies are never included.
+        headers: reqwest::header::HeaderMap,
+        /// Response body text, truncated to a few kilobytes.
+        body: String,
+        /// ID of the failed request; see [`Error::request_id`].
+        request_id: Option<String>,
+    },
+
+    /// GuerrillaMail asked the client to slow down.
+    ///
+    /// Raised for HTTP 429, and for 503 responses that carry a `Retry-After` header.
+    /// `retry_after` holds the advertised wait, when the server sent one. Unlike `Api`, this is
+    /// always transient: wait and try again.
+    #[error("Rate limited by GuerrillaMail (retry after: {retry_after:?})")]
+    RateLimited {
+        /// How long the server asked us to wait before retrying.
+        retry_after: Option<std::time::Duration>,
+        /// ID of the throttled request; see [`Error::request_id`].
+        request_id: Option<String>,
+    },
+
+    /// The circuit breaker is open after repeated upstream failures; no request was sent.
+    ///
+    /// See `ClientBuilder::circuit_breaker`. `retry_in` is the remaining cool-down, or zero while
+    /// another call is probing whether GuerrillaMail has recovered.
+    #[error("Circuit open after repeated GuerrillaMail failures (retry in {retry_in:?})")]
+    CircuitOpen {
+        /// Time until the circuit lets a probe request through.
+        retry_in: std::time::Duration,
+    },
+
+    /// The operation was stopped through its `CancellationToken`.
+    #[error("Operation cancelled")]
+    Cancelled,
 
     /// Response was received but did not match the expected shape/content.
     ///
     /// Use this for “missing field”, “unexpected type”, or “schema changed” cases.
-    #[error("Unexpected GuerrillaMail response: {0}")]
-    ResponseParse(&'static str),
+    #[error("Unexpected GuerrillaMail response: {reason}")]
+    ResponseParse {
+        /// What was wrong with the response.
+        reason: &'static str,
+        /// ID of the request that got the response; see [`Error::request_id`].
+        request_id: Option<String>,
+    },
+
+    /// A GuerrillaMail response body could not be deserialized into the expected type.
+    ///
+    /// `path` points at the offending field (for example `att_info[0].p`, or `.` when the body
+    /// is not JSON at all), and `snippet` holds the start of the body as received.
+    #[error("Failed to deserialize GuerrillaMail response at `{path}`: {source} (body: {snippet})")]
+    Deserialize {
+        /// Path to the field that failed, in `serde_path_to_error` notation.
+        path: String,
+        /// The start of the response body, truncated to a few hundred characters, with the
+        /// values of token fields such as `sid_token` replaced by `***`.
+        snippet: String,
+        /// The underlying serde error.
+        #[source]
+        source: serde_json::Error,
+        /// ID of the request that got the body; see [`Error::request_id`].
+        request_id: Option<String>,
+    },
+
+    /// Some inbox entries could not be deserialized into [`Message`]s.
+    ///
+    /// `messages` holds the entries that did parse, so callers can carry on with a partial
+    /// inbox; `failures` lists the skipped entries and why they failed, which usually means
+    /// GuerrillaMail changed its schema.
+    #[error(
+        "{} of {} inbox entries failed to parse",
+        failures.len(),
+        failures.len() + messages.len()
+    )]
+    InvalidMessages {
+        /// Entries that deserialized successfully, in inbox order.
+        messages: Vec<Message>,
+        /// Entries that failed to deserialize; never empty.
+        failures: Vec<EntryFailure>,
+    },
 
     /// Failed to parse the API token from the GuerrillaMail homepage.
     ///
-    /// This error typically occurs during client construction when
-    /// the expected `api_token` JavaScript variable cannot be found
-    /// or does not match the expected format.
+    /// This error typically occurs during client construction when the
+    /// JSON bootstrap failed and none of the known `api_token` patterns
+    /// match the homepage.
     #[error("Failed to parse API token from GuerrillaMail page")]
     TokenParse,
 
+    /// GuerrillaMail (or a CDN in front of it) answered with an anti-bot challenge page.
+    ///
+    /// Returned instead of `TokenParse` or a JSON decode failure when the body looks like a
+    /// Cloudflare, DDoS-Guard, or captcha interstitial. Retrying from the same network address
+    /// usually gets the same page; switch proxies or back off instead.
+    #[error("Anti-bot challenge page detected: {body_excerpt}")]
+    ChallengeDetected {
+        /// The start of the challenge page body, for logging.
+        body_excerpt: String,
+        /// ID of the request that got the page; see [`Error::request_id`].
+        request_id: Option<String>,
+    },
+
     /// Failed to parse the available domain list from the GuerrillaMail page.
     ///
     /// This indicates that the service response structure may have changed
@@ -42,6 +147,45 @@ pub enum Error {
     #[error("Failed to parse domain list from GuerrillaMail page")]
     DomainParse,
 
+    /// The requested domain is not one of the domains GuerrillaMail offers.
+    ///
+    /// Only raised once the client has cached the domain list (see `Client::domains`).
+    #[error("Unsupported GuerrillaMail domain: {0}")]
+    UnsupportedDomain(String),
+
+    /// An alias would not be accepted by GuerrillaMail as-is; nothing was sent.
+    ///
+    /// See [`alias::validate`](crate::alias::validate) for the rules.
+    #[error("Invalid alias `{alias}`: {reason}")]
+    InvalidAlias {
+        /// The alias as given.
+        alias: String,
+        /// Which rule it breaks.
+        reason: String,
+    },
+
+    /// An address passed to the client is malformed; nothing was sent.
+    ///
+    /// See [`EmailAddress::parse`](crate::EmailAddress::parse) for the rules.
+    #[error("Invalid email address `{address}`: {reason}")]
+    InvalidAddress {
+        /// The address as given.
+        address: String,
+        /// What is wrong with it.
+        reason: &'static str,
+    },
+
+    /// No link in the message matched the [`LinkSelector`](crate::LinkSelector).
+    #[error("No matching link in message {mail_id}")]
+    LinkNotFound {
+        /// The message that was searched.
+        mail_id: String,
+    },
+
+    /// A proxy URL could not be parsed into a `ProxyConfig`.
+    #[error("Invalid proxy: {0}")]
+    InvalidProxy(String),
+
     /// Failed to build or parse a regex used by the client.
     #[error("Regex error: {0}")]
     Regex(#[from] regex::Error),
@@ -50,6 +194,14 @@ pub enum Error {
     #[error("Invalid header value: {0}")]
     HeaderValue(#[from] reqwest::header::InvalidHeaderValue),
 
+    /// A header name given to the builder is not a valid HTTP header name.
+    #[error("Invalid header name: {0}")]
+    HeaderName(#[from] reqwest::header::InvalidHeaderName),
+
+    /// A filesystem operation failed (e.g. saving or loading a session).
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
     /// Failed to deserialize JSON returned by the GuerrillaMail API.
     ///
     /// This usually indicates an unexpected response schema or a
@@ -57,3 +209,79 @@ pub enum Error {
     #[error("JSON parsing error: {0}")]
     Json(#[from] serde_json::Error),
 }
+
+impl From<reqwest::Error> for Error {
+    fn from(source: reqwest::Error) -> Self {
+        Error::Request {
+            source,
+            request_id: None,
+        }
+    }
+}
+
+impl Error {
+    /// Client-generated ID of the request that failed, when the error carries one.
+    ///
+    /// Every request gets a fresh ID that also appears in its `tracing` span, request log
+    /// record, and [`RequestMetrics`](crate::RequestMetrics), so a failure in a large
+    /// concurrent run can be matched to the exact exchange. Set on `Request`, `Api`,
+    /// `RateLimited`, `ChallengeDetected`, `Deserialize`, and `ResponseParse` errors that came
+    /// from an exchange; always `None` on wasm32.
+    pub fn request_id(&self) -> Option<&str> {
+        match self {
+            Error::Request { request_id, .. }
+            | Error::Api { request_id, .. }
+            | Error::RateLimited { request_id, .. }
+            | Error::ResponseParse { request_id, .. }
+            | Error::Deserialize { request_id, .. }
+            | Error::ChallengeDetected { request_id, .. } => request_id.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// `Error::ResponseParse` for a response whose request ID is unknown.
+    pub(crate) fn response_parse(reason: &'static str) -> Self {
+        Error::ResponseParse {
+            reason,
+            request_id: None,
+        }
+    }
+
+    /// Attach `id` to an error that can carry one and does not have one yet.
+    pub(crate) fn with_request_id(mut self, id: Option<String>) -> Self {
+        if let Error::Request { request_id, .. }
+        | Error::Api { request_id, .. }
+        | Error::RateLimited { request_id, .. }
+        | Error::ResponseParse { request_id, .. }
+        | Error::Deserialize { request_id, .. }
+        | Error::ChallengeDetected { request_id, .. } = &mut self
+            && request_id.is_none()
+        {
+            *request_id = id;
+        }
+        self
+    }
+}
+
+/// An inbox entry that could not be deserialized, as reported by [`Error::InvalidMessages`].
+#[derive(Debug)]
+pub struct EntryFailure {
+    /// Position of the entry in the `list` array returned by GuerrillaMail.
+    pub index: usize,
+    /// The entry's `mail_id`, when it has a readable one.
+    pub mail_id: Option<String>,
+    /// Path to the field that failed, relative to the entry (for example `mail_id`).
+    pub path: String,
+    /// Why deserialization failed.
+    pub error: serde_json::Error,
+}
+
+impl fmt::Display for EntryFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.mail_id {
+            Some(mail_id) => write!(f, "entry {} (mail_id {mail_id})", self.index)?,
+            None => write!(f, "entry {}", self.index)?,
+        }
+        write!(f, " at `{}`: {}", self.path, self.error)
+    }
+}
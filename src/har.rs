@@ -0,0 +1,367 @@
+//! HAR (HTTP Archive) recording of client traffic.
+
+use crate::middleware::{form_pairs, query_pairs, redact};
+use crate::{Error, Result};
+use reqwest::header::{AUTHORIZATION, COOKIE, HeaderMap, SET_COOKIE};
+use serde_json::{Value, json};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use web_time::{SystemTime, UNIX_EPOCH};
+
+/// Collects every request/response pair of a client as HAR 1.2 entries.
+///
+/// Attach with [`ClientBuilder::record_har`], run the client, then [`save`](HarRecorder::save)
+/// the archive and open it in a browser's network panel or any HAR viewer. Recording covers
+/// bootstrap, API, and attachment requests exactly as sent, after interceptors have run.
+///
+/// Secrets are redacted before they are stored: `Authorization`, `Cookie`, and `Set-Cookie`
+/// header values, parameters whose names contain `token`, and `sid_token` fields in JSON
+/// bodies all become `***`. Message contents are kept as is.
+///
+/// Clones share the same entries. Only available with the `har` cargo feature.
+///
+/// [`ClientBuilder::record_har`]: crate::ClientBuilder::record_har
+///
+/// # Example
+/// ```no_run
+/// use guerrillamail_client::{Client, HarRecorder};
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), guerrillamail_client::Error> {
+/// let har = HarRecorder::new();
+/// let client = Client::builder().record_har(har.clone()).build().await?;
+/// client.create_email("demo").await?;
+/// har.save("guerrillamail.har")?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct HarRecorder {
+    entries: Arc<Mutex<Vec<Value>>>,
+}
+
+/// What was sent, captured before the request is handed to the transport.
+pub(crate) struct SentRequest {
+    started: SystemTime,
+    method: String,
+    url: reqwest::Url,
+    version: reqwest::Version,
+    headers: HeaderMap,
+    body: Option<Vec<u8>>,
+}
+
+impl SentRequest {
+    pub(crate) fn new(request: &reqwest::Request) -> Self {
+        Self {
+            started: SystemTime::now(),
+            method: request.method().to_string(),
+            url: request.url().clone(),
+            version: request.version(),
+            headers: request.headers().clone(),
+            body: request
+                .body()
+                .and_then(|b| b.as_bytes())
+                .map(<[u8]>::to_vec),
+        }
+    }
+}
+
+impl HarRecorder {
+    /// Create an empty recorder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of recorded entries.
+    pub fn len(&self) -> usize {
+        self.entries.lock().expect("har lock poisoned").len()
+    }
+
+    /// Whether nothing has been recorded yet.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The archive as a HAR 1.2 JSON document.
+    pub fn to_json(&self) -> Value {
+        let entries = self.entries.lock().expect("har lock poisoned").clone();
+        json!({
+            "log": {
+                "version": "1.2",
+                "creator": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+                "entries": entries,
+            }
+        })
+    }
+
+    /// Write the archive to `path` as pretty-printed JSON.
+    ///
+    /// # Errors
+    /// Returns `Error::Json` if serialization fails or `Error::Io` if the file cannot be
+    /// written.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let json = serde_json::to_vec_pretty(&self.to_json())?;
+        std::fs::write(path, json).map_err(Error::Io)
+    }
+
+    /// Record one exchange; `response` is the error when no response was received.
+    pub(crate) fn record(
+        &self,
+        sent: SentRequest,
+        response: std::result::Result<&http::Response<Vec<u8>>, &Error>,
+    ) {
+        let elapsed = sent.started.elapsed().unwrap_or(Duration::ZERO);
+        let body_text = sent
+            .body
+            .as_deref()
+            .map(|body| redact_body(body, &sent.headers));
+        let request = json!({
+            "method": sent.method,
+            "url": redacted_url(&sent.url),
+            "httpVersion": format!("{:?}", sent.version),
+            "headers": har_headers(&sent.headers),
+            "queryString": har_pairs(redact(query_pairs(&sent.url))),
+            "cookies": [],
+            "headersSize": -1,
+            "bodySize": sent.body.as_ref().map_or(0, |body| body.len() as i64),
+            "postData": body_text.map(|text| json!({
+                "mimeType": content_type(&sent.headers),
+                "text": text,
+            })),
+        });
+        let response = match response {
+            Ok(response) => json!({
+                "status": response.status().as_u16(),
+                "statusText": response.status().canonical_reason().unwrap_or_default(),
+                "httpVersion": format!("{:?}", response.version()),
+                "headers": har_headers(response.headers()),
+                "cookies": [],
+                "content": {
+                    "size": response.body().len(),
+                    "mimeType": content_type(response.headers()),
+                    "text": redact_body(response.body(), response.headers()),
+                },
+                "redirectURL": "",
+                "headersSize": -1,
+                "bodySize": response.body().len(),
+            }),
+            Err(err) => json!({
+                "status": 0,
+                "statusText": "",
+                "httpVersion": "",
+                "headers": [],
+                "cookies": [],
+                "content": { "size": 0, "mimeType": "" },
+                "redirectURL": "",
+                "headersSize": -1,
+                "bodySize": -1,
+                "_error": err.to_string(),
+            }),
+        };
+        let millis = elapsed.as_secs_f64() * 1000.0;
+        let entry = json!({
+            "startedDateTime": iso8601(sent.started),
+            "time": millis,
+            "request": request,
+            "response": response,
+            "cache": {},
+            "timings": { "send": 0, "wait": millis, "receive": 0 },
+        });
+        self.entries.lock().expect("har lock poisoned").push(entry);
+    }
+}
+
+/// Headers as HAR name/value objects, with credentials redacted.
+fn har_headers(headers: &HeaderMap) -> Vec<Value> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = if [AUTHORIZATION, COOKIE, SET_COOKIE].contains(name) {
+                "***".into()
+            } else {
+                String::from_utf8_lossy(value.as_bytes()).into_owned()
+            };
+            json!({ "name": name.as_str(), "value": value })
+        })
+        .collect()
+}
+
+fn har_pairs(pairs: Vec<(String, String)>) -> Vec<Value> {
+    pairs
+        .into_iter()
+        .map(|(name, value)| json!({ "name": name, "value": value }))
+        .collect()
+}
+
+fn content_type(headers: &HeaderMap) -> &str {
+    headers
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+}
+
+/// `url` with token-like query values redacted.
+fn redacted_url(url: &reqwest::Url) -> String {
+    let mut url = url.clone();
+    let pairs = redact(query_pairs(&url));
+    if !pairs.is_empty() {
+        url.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+    url.into()
+}
+
+/// Body as text, with token-like form parameters and JSON `sid_token` fields redacted.
+fn redact_body(body: &[u8], headers: &HeaderMap) -> String {
+    let content_type = content_type(headers);
+    if content_type.starts_with("application/x-www-form-urlencoded") {
+        let mut url = reqwest::Url::parse("http://form.invalid/").expect("static url is valid");
+        url.query_pairs_mut().extend_pairs(redact(form_pairs(body)));
+        return url.query().unwrap_or_default().to_string();
+    }
+    match serde_json::from_slice::<Value>(body) {
+        Ok(mut json) => {
+            redact_json(&mut json);
+            json.to_string()
+        }
+        Err(_) => String::from_utf8_lossy(body).into_owned(),
+    }
+}
+
+fn redact_json(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if key.to_ascii_lowercase().contains("token") {
+                    *value = "***".into();
+                } else {
+                    redact_json(value);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_json),
+        _ => {}
+    }
+}
+
+/// Format `time` as an ISO 8601 UTC timestamp with millisecond precision.
+fn iso8601(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or(Duration::ZERO);
+    let secs = since_epoch.as_secs();
+    let (days, secs_of_day) = ((secs / 86_400) as i64, secs % 86_400);
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm).
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:03}Z",
+        secs_of_day / 3_600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60,
+        since_epoch.subsec_millis()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::session_client;
+    use crate::{Client, Session};
+    use httpmock::Method::GET;
+    use httpmock::MockServer;
+
+    #[test]
+    fn iso8601_formats_utc_timestamps() {
+        let time = UNIX_EPOCH + Duration::from_millis(1_709_251_200_123);
+        assert_eq!(iso8601(time), "2024-03-01T00:00:00.123Z");
+        assert_eq!(iso8601(UNIX_EPOCH), "1970-01-01T00:00:00.000Z");
+    }
+
+    #[tokio::test]
+    async fn records_exchanges_with_secrets_redacted() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET).path("/ajax.php");
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(json!({ "list": [], "sid_token": "secret-sid" }));
+        });
+        let har = HarRecorder::new();
+        let client = Client::builder()
+            .record_har(har.clone())
+            .base_url(server.base_url())
+            .ajax_url(server.url("/ajax.php"))
+            .session(Session {
+                api_token: "secret-token".to_string(),
+                sid_token: None,
+                cookies: String::new(),
+            })
+            .build()
+            .await
+            .unwrap();
+
+        client.get_messages("alias@example.com").await.unwrap();
+
+        assert_eq!(har.len(), 1);
+        let archive = har.to_json();
+        let entry = &archive["log"]["entries"][0];
+        assert_eq!(entry["request"]["method"], "GET");
+        assert_eq!(entry["response"]["status"], 200);
+        let text = archive.to_string();
+        assert!(
+            !text.contains("secret-token") && !text.contains("secret-sid"),
+            "{text}"
+        );
+        assert!(text.contains("check_email"));
+    }
+
+    #[tokio::test]
+    async fn recorded_responses_keep_their_url() {
+        let server = MockServer::start();
+        let base_url = server.base_url();
+        server.mock(|when, then| {
+            when.method(GET).path("/ajax.php");
+            then.status(200).json_body(json!({
+                "mail_id": "1",
+                "mail_from": "a@example.com",
+                "mail_subject": "Welcome",
+                "mail_body": format!(r#"<a href="{base_url}/verify">Verify</a>"#),
+                "mail_timestamp": "1700000000",
+            }));
+        });
+        server.mock(|when, then| {
+            when.method(GET).path("/verify");
+            then.status(302).header("Location", "/welcome");
+        });
+        server.mock(|when, then| {
+            when.method(GET).path("/welcome");
+            then.status(200).body("verified");
+        });
+        let har = HarRecorder::new();
+        let client = session_client(&server.base_url())
+            .record_har(har.clone())
+            .build()
+            .await
+            .unwrap();
+
+        let details = client.fetch_email("alias@example.com", "1").await.unwrap();
+        let visit = client
+            .click_verification_link(&details, crate::LinkSelector::First)
+            .await
+            .unwrap();
+
+        assert_eq!(visit.final_url.as_str(), server.url("/welcome"));
+        assert_eq!(har.len(), 2);
+    }
+}
@@ -0,0 +1,662 @@
+//! Plain-text rendering, sanitization, and link extraction for HTML message bodies.
+
+/// Elements whose content is never shown.
+const HIDDEN: [&str; 4] = ["head", "script", "style", "template"];
+
+/// Elements that start a new line.
+const BLOCKS: [&str; 22] = [
+    "address",
+    "article",
+    "blockquote",
+    "br",
+    "dd",
+    "div",
+    "dl",
+    "dt",
+    "footer",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "li",
+    "p",
+    "section",
+    "table",
+    "tr",
+];
+
+/// Render `html` as readable text.
+///
+/// Tags are dropped, with block elements (`p`, `div`, `br`, `li`, table rows, ...) turned into
+/// line breaks; comments and the contents of `head`, `script`, and `style` are skipped; named and
+/// numeric character references are resolved; runs of spaces are collapsed, lines are trimmed,
+/// and blank lines are squeezed to one.
+pub(crate) fn to_text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut hidden: Option<String> = None;
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        if hidden.is_none() {
+            push_text(&mut text, &rest[..start]);
+        }
+        rest = &rest[start..];
+        if let Some(after) = rest.strip_prefix("<!--") {
+            rest = after.find("-->").map_or("", |end| &after[end + 3..]);
+            continue;
+        }
+        // A `<` that is never closed, as in "I <3 you", is text.
+        let Some(end) = rest.find('>') else {
+            break;
+        };
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+
+        let closing = tag.starts_with('/');
+        let name: String = tag
+            .trim_start_matches('/')
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric())
+            .map(|c| c.to_ascii_lowercase())
+            .collect();
+        match &hidden {
+            Some(open) if closing && *open == name => hidden = None,
+            Some(_) => {}
+            None if !closing && HIDDEN.contains(&name.as_str()) && !tag.ends_with('/') => {
+                hidden = Some(name);
+            }
+            None if BLOCKS.contains(&name.as_str()) => text.push('\n'),
+            None if matches!(name.as_str(), "td" | "th") => text.push(' '),
+            None => {}
+        }
+    }
+    if hidden.is_none() {
+        push_text(&mut text, rest);
+    }
+    normalize_whitespace(&text)
+}
+
+/// A link found in a message body by [`EmailDetails::links`](crate::EmailDetails::links).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct ExtractedLink {
+    /// The link target, with character references such as `&amp;` resolved.
+    pub url: String,
+    /// The anchor text as plain text, or `None` for a URL written out in the body text.
+    pub text: Option<String>,
+    /// Byte offset in the body where the link starts (its `<a` tag, or the URL itself).
+    pub offset: usize,
+}
+
+/// Which link [`Client::click_verification_link`](crate::Client::click_verification_link)
+/// follows. Only `http` and `https` links are considered, and the first match in body order wins.
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub enum LinkSelector {
+    /// A link whose URL or anchor text mentions verifying, confirming, activating, or validating.
+    #[default]
+    Verification,
+    /// The first link.
+    First,
+    /// A link whose URL contains the given substring.
+    UrlContains(String),
+    /// A link whose anchor text contains the given substring, ignoring case.
+    TextContains(String),
+    /// A link whose URL matches the regex.
+    UrlMatches(regex::Regex),
+}
+
+impl LinkSelector {
+    /// The first link in `links` this selector accepts.
+    pub fn select<'a>(&self, links: &'a [ExtractedLink]) -> Option<&'a ExtractedLink> {
+        links
+            .iter()
+            .filter(|link| is_web(&link.url))
+            .find(|link| self.accepts(link))
+    }
+
+    fn accepts(&self, link: &ExtractedLink) -> bool {
+        let text = link.text.as_deref().unwrap_or_default().to_lowercase();
+        match self {
+            LinkSelector::Verification => {
+                let url = link.url.to_lowercase();
+                ["verif", "confirm", "activat", "validat"]
+                    .iter()
+                    .any(|word| url.contains(word) || text.contains(word))
+            }
+            LinkSelector::First => true,
+            LinkSelector::UrlContains(needle) => link.url.contains(needle.as_str()),
+            LinkSelector::TextContains(needle) => text.contains(&needle.to_lowercase()),
+            LinkSelector::UrlMatches(regex) => regex.is_match(&link.url),
+        }
+    }
+}
+
+fn is_web(url: &str) -> bool {
+    let scheme = url.split_once(':').map_or("", |(scheme, _)| scheme);
+    scheme.eq_ignore_ascii_case("http") || scheme.eq_ignore_ascii_case("https")
+}
+
+/// The outcome of [`Client::click_verification_link`](crate::Client::click_verification_link).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct LinkVisit {
+    /// The link that was followed, as it appears in the message.
+    pub url: String,
+    /// Status of the last response, after redirects.
+    pub status: reqwest::StatusCode,
+    /// URL of the last response, after redirects.
+    pub final_url: reqwest::Url,
+}
+
+/// Every link in `html`: `<a href>` targets with their anchor text, and bare `http(s)://` URLs
+/// in the text outside anchors, in body order.
+///
+/// Fragment-only (`#top`) and `javascript:` targets are skipped.
+pub(crate) fn links(html: &str) -> Vec<ExtractedLink> {
+    let mut links = Vec::new();
+    // Target, offset of the `<a` tag, and start of the anchor text.
+    let mut anchor: Option<(String, usize, usize)> = None;
+    let mut hidden: Option<String> = None;
+    let mut pos = 0;
+    while let Some(found) = html[pos..].find('<') {
+        let start = pos + found;
+        if anchor.is_none() && hidden.is_none() {
+            text_urls(html, pos, start, &mut links);
+        }
+        if html[start..].starts_with("<!--") {
+            pos = html[start + 4..]
+                .find("-->")
+                .map_or(html.len(), |end| start + end + 7);
+            continue;
+        }
+        let Some(end) = html[start..].find('>') else {
+            pos = html.len();
+            break;
+        };
+        let tag = &html[start + 1..start + end];
+        pos = start + end + 1;
+
+        let closing = tag.starts_with('/');
+        let (name, attributes) = parse_tag(tag.trim_start_matches('/'));
+        match &hidden {
+            Some(open) if closing && *open == name => hidden = None,
+            Some(_) => {}
+            None if !closing && HIDDEN.contains(&name.as_str()) && !tag.ends_with('/') => {
+                hidden = Some(name);
+            }
+            None if name == "a" => {
+                if let Some((url, offset, text_start)) = anchor.take() {
+                    let text = to_text(&html[text_start..start]);
+                    links.push(ExtractedLink {
+                        url,
+                        text: Some(text),
+                        offset,
+                    });
+                }
+                if closing {
+                    continue;
+                }
+                let href = attributes.into_iter().find(|(key, _)| key == "href");
+                let url = href.map(|(_, value)| resolve_entities(value.trim()));
+                if let Some(url) = url.filter(|url| is_followable(url)) {
+                    anchor = Some((url, start, pos));
+                }
+            }
+            None => {}
+        }
+    }
+    match anchor {
+        Some((url, offset, text_start)) => {
+            let text = to_text(&html[text_start.min(html.len())..]);
+            links.push(ExtractedLink {
+                url,
+                text: Some(text),
+                offset,
+            });
+        }
+        None if hidden.is_none() => text_urls(html, pos, html.len(), &mut links),
+        None => {}
+    }
+    links
+}
+
+/// Whether an `href` points somewhere a client could go.
+fn is_followable(url: &str) -> bool {
+    let lower = url.to_ascii_lowercase();
+    !url.is_empty() && !url.starts_with('#') && !lower.starts_with("javascript:")
+}
+
+/// Collect bare `http(s)://` URLs in `html[from..to]`.
+fn text_urls(html: &str, from: usize, to: usize, links: &mut Vec<ExtractedLink>) {
+    let segment = &html[from..to];
+    let lower = segment.to_ascii_lowercase();
+    let mut pos = 0;
+    while let Some(found) = lower[pos..].find("http") {
+        let start = pos + found;
+        let candidate = &segment[start..];
+        let is_url = ["http://", "https://"]
+            .iter()
+            .any(|scheme| lower[start..].starts_with(scheme));
+        let len = candidate
+            .find(|c: char| c.is_whitespace() || matches!(c, '<' | '>' | '"' | '\''))
+            .unwrap_or(candidate.len());
+        let url = candidate[..len].trim_end_matches(['.', ',', ';', ':', '!', '?', ')', ']']);
+        if is_url && url.len() > "https://".len() {
+            links.push(ExtractedLink {
+                url: resolve_entities(url),
+                text: None,
+                offset: from + start,
+            });
+        }
+        pos = start + len.max(4);
+    }
+}
+
+/// `text` with character references resolved.
+fn resolve_entities(text: &str) -> String {
+    let mut resolved = String::with_capacity(text.len());
+    push_text(&mut resolved, text);
+    resolved
+}
+
+/// Elements dropped together with their content by [`sanitize`].
+const UNSAFE: [&str; 14] = [
+    "applet", "embed", "frame", "frameset", "head", "iframe", "math", "noscript", "object",
+    "script", "style", "svg", "template", "title",
+];
+
+/// Elements dropped by [`sanitize`] whose content is kept or which have none.
+const STRIPPED: [&str; 4] = ["base", "form", "link", "meta"];
+
+/// Attributes that hold a URL.
+const URL_ATTRIBUTES: [&str; 8] = [
+    "action",
+    "background",
+    "formaction",
+    "href",
+    "poster",
+    "src",
+    "srcset",
+    "xlink:href",
+];
+
+/// Make `html` safe to render: no scripts, no event handlers, no remote trackers.
+///
+/// Removes `head`, `script`, `style`, `iframe`, `object`, `embed`, `svg`, and similar elements
+/// with their content; drops `meta`, `link`, `base`, and `form` tags; strips `on*` event handler
+/// attributes, URLs with any scheme but `http`, `https`, `mailto`, `cid`, and `data:image/`
+/// (checked after resolving character references and dropping tabs and newlines, as browsers
+/// do), and `style` attributes that load URLs; and removes images and backgrounds loaded from
+/// remote servers, which are how senders track opens. Comments are dropped. Everything else is
+/// kept as is.
+pub(crate) fn sanitize(html: &str) -> String {
+    let mut clean = String::with_capacity(html.len());
+    let mut hidden: Option<String> = None;
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        if hidden.is_none() {
+            clean.push_str(&rest[..start]);
+        }
+        rest = &rest[start..];
+        if let Some(after) = rest.strip_prefix("<!--") {
+            rest = after.find("-->").map_or("", |end| &after[end + 3..]);
+            continue;
+        }
+        let Some(end) = rest.find('>') else {
+            rest = "";
+            break;
+        };
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+
+        let closing = tag.starts_with('/');
+        let (name, attributes) = parse_tag(tag.trim_start_matches('/'));
+        if let Some(open) = &hidden {
+            if closing && *open == name {
+                hidden = None;
+            }
+            continue;
+        }
+        if UNSAFE.contains(&name.as_str()) {
+            if !closing && !tag.ends_with('/') {
+                hidden = Some(name);
+            }
+            continue;
+        }
+        if name.is_empty() || STRIPPED.contains(&name.as_str()) {
+            continue;
+        }
+        if closing {
+            clean.push_str(&format!("</{name}>"));
+            continue;
+        }
+
+        let mut kept = String::new();
+        let mut tracker = false;
+        for (attribute, value) in attributes {
+            // Checks and output both use the value as a browser reads it, so character
+            // references cannot smuggle anything past the checks.
+            let value = resolve_entities(&value);
+            let lower = url_text(&value);
+            let remote = ["http:", "https:", "//"]
+                .iter()
+                .any(|p| lower.starts_with(p));
+            if attribute.starts_with("on") || attribute == "srcdoc" {
+                continue;
+            }
+            if URL_ATTRIBUTES.contains(&attribute.as_str()) {
+                if !is_safe_url(&lower) {
+                    continue;
+                }
+                if remote && name == "img" && matches!(attribute.as_str(), "src" | "srcset") {
+                    tracker = true;
+                }
+                if remote && attribute == "background" {
+                    continue;
+                }
+            }
+            if attribute == "style" && (lower.contains("url(") || lower.contains("expression(")) {
+                continue;
+            }
+            kept.push_str(&format!(" {attribute}=\"{}\"", escape_attribute(&value)));
+        }
+        if !tracker {
+            let self_closing = if tag.ends_with('/') { " /" } else { "" };
+            clean.push_str(&format!("<{name}{kept}{self_closing}>"));
+        }
+    }
+    if hidden.is_none() {
+        clean.push_str(rest);
+    }
+    clean
+}
+
+/// `value` lowercased the way a browser reads a URL: with ASCII tabs, newlines, and other
+/// control characters removed and surrounding spaces trimmed.
+fn url_text(value: &str) -> String {
+    let stripped: String = value.chars().filter(|c| !c.is_ascii_control()).collect();
+    stripped.trim().to_ascii_lowercase()
+}
+
+/// Whether a URL (as returned by [`url_text`]) is relative or uses an allowed scheme: `http`,
+/// `https`, `mailto`, `cid`, or `data:` for images.
+fn is_safe_url(url: &str) -> bool {
+    let Some((scheme, _)) = url.split_once(':') else {
+        return true;
+    };
+    if scheme.contains(['/', '?', '#']) {
+        // The colon is in the path, query, or fragment of a relative URL.
+        return true;
+    }
+    match scheme {
+        "http" | "https" | "mailto" | "cid" => true,
+        "data" => url.starts_with("data:image/"),
+        _ => false,
+    }
+}
+
+/// `value` escaped for a double-quoted attribute.
+fn escape_attribute(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Split the inside of a start tag into its lowercased name and its attributes.
+fn parse_tag(tag: &str) -> (String, Vec<(String, String)>) {
+    let tag = tag.trim_end_matches('/');
+    let name_end = tag
+        .find(|c: char| c.is_whitespace() || c == '/')
+        .unwrap_or(tag.len());
+    let name = tag[..name_end].to_ascii_lowercase();
+    let name = if name.chars().all(|c| c.is_ascii_alphanumeric()) {
+        name
+    } else {
+        String::new()
+    };
+
+    let mut attributes = Vec::new();
+    let mut rest = &tag[name_end..];
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == '/');
+        if rest.is_empty() {
+            break;
+        }
+        let key_end = rest
+            .find(|c: char| c.is_whitespace() || c == '=' || c == '/')
+            .unwrap_or(rest.len());
+        let key = rest[..key_end].to_ascii_lowercase();
+        rest = rest[key_end..].trim_start();
+        let value = match rest.strip_prefix('=') {
+            Some(after) => {
+                let after = after.trim_start();
+                let (value, remaining) = match after.chars().next() {
+                    Some(quote @ ('"' | '\'')) => {
+                        let body = &after[1..];
+                        let end = body.find(quote).unwrap_or(body.len());
+                        (&body[..end], body.get(end + 1..).unwrap_or(""))
+                    }
+                    _ => {
+                        let end = after.find(char::is_whitespace).unwrap_or(after.len());
+                        after.split_at(end)
+                    }
+                };
+                rest = remaining;
+                value.to_string()
+            }
+            None => String::new(),
+        };
+        if !key.is_empty() {
+            attributes.push((key, value));
+        }
+    }
+    (name, attributes)
+}
+
+/// Formatting characters without a glyph: soft hyphen, zero-width non-joiner, and zero-width
+/// joiner. References to them are dropped so they neither show up nor split words.
+const INVISIBLE: [char; 3] = ['\u{ad}', '\u{200c}', '\u{200d}'];
+
+/// Append a run of character data, resolving character references.
+fn push_text(text: &mut String, data: &str) {
+    let mut rest = data;
+    while let Some(start) = rest.find('&') {
+        text.push_str(&rest[..start]);
+        rest = &rest[start..];
+        match rest[1..].find(';').filter(|&end| end <= 32) {
+            Some(end) if let Some(c) = resolve_entity(&rest[1..=end]) => {
+                if !INVISIBLE.contains(&c) {
+                    text.push(c);
+                }
+                rest = &rest[end + 2..];
+            }
+            _ => {
+                text.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    text.push_str(rest);
+}
+
+/// The character a reference such as `amp`, `#39`, or `#x27` stands for.
+fn resolve_entity(name: &str) -> Option<char> {
+    if let Some(number) = name.strip_prefix('#') {
+        let code = match number.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => number.parse().ok()?,
+        };
+        return char::from_u32(code);
+    }
+    Some(match name {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => ' ',
+        "copy" => '©',
+        "reg" => '®',
+        "trade" => '™',
+        "hellip" => '…',
+        "mdash" => '—',
+        "ndash" => '–',
+        "lsquo" => '‘',
+        "rsquo" => '’',
+        "ldquo" => '“',
+        "rdquo" => '”',
+        "laquo" => '«',
+        "raquo" => '»',
+        "bull" => '•',
+        "middot" => '·',
+        "euro" => '€',
+        "pound" => '£',
+        "shy" => '\u{ad}',
+        "zwnj" => '\u{200c}',
+        "zwj" => '\u{200d}',
+        _ => return None,
+    })
+}
+
+/// Collapse spaces within lines, trim lines, and keep at most one blank line in a row.
+fn normalize_whitespace(text: &str) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        let line = line
+            .split(|c: char| c.is_whitespace() || c == '\u{200b}')
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        if !line.is_empty() || lines.last().is_some_and(|last| !last.is_empty()) {
+            lines.push(line);
+        }
+    }
+    while lines.last().is_some_and(String::is_empty) {
+        lines.pop();
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_markup_as_readable_text() {
+        let html = "<html><head><title>Hi</title><style>p { color: red }</style></head>\
+            <body><!-- tracking --><p>Your  code is <b>123&nbsp;456</b></p>\
+            <p>Tom &amp; Jerry&#39;s &#x2603; &bogus; a&b</p><br><br><br>\
+            <table><tr><td>one</td><td>two</td></tr></table></body></html>";
+
+        assert_eq!(
+            to_text(html),
+            "Your code is 123 456\n\nTom & Jerry's ☃ &bogus; a&b\n\none two"
+        );
+    }
+
+    #[test]
+    fn invisible_references_do_not_split_words() {
+        let html = "<p>Your code is 12&shy;34&zwnj;56, ver&#173;i&zwj;fy</p>";
+
+        assert_eq!(to_text(html), "Your code is 123456, verify");
+    }
+
+    #[test]
+    fn unclosed_angle_brackets_are_text() {
+        assert_eq!(to_text("love you <3 😊"), "love you <3 😊");
+        assert_eq!(to_text("<p>a <é"), "a <é");
+    }
+
+    #[test]
+    fn sanitize_removes_scripts_handlers_and_trackers() {
+        let html = "<head><title>x</title><meta http-equiv=refresh content=0></head>\
+            <p onclick=\"steal()\" class='intro'>Hi <b>there</b></p>\
+            <script>alert(1)</script><SCRIPT src=//evil.example></SCRIPT>\
+            <a href=\"javascript:alert(1)\">bad</a> <a href=\"https://example.com/v?t=1\">ok</a>\
+            <img src=\"https://t.example/open.gif\" width=1>\
+            <img src=\"data:image/png;base64,AA==\">\
+            <div style=\"background:url(https://t.example/bg)\" background=\"http://t.example\">\
+            <iframe src=\"https://evil.example\"></iframe><!-- note --></div>";
+
+        assert_eq!(
+            sanitize(html),
+            "<p class=\"intro\">Hi <b>there</b></p><a>bad</a> \
+             <a href=\"https://example.com/v?t=1\">ok</a>\
+             <img src=\"data:image/png;base64,AA==\"><div></div>"
+        );
+    }
+
+    #[test]
+    fn sanitize_sees_urls_the_way_browsers_do() {
+        let html = "<a href=\"&#106;avascript:alert(1)\">a</a>\
+            <a href=\"java\tscript:alert(1)\">b</a>\
+            <a href=\"jav&#x0A;ascript:alert(1)\">c</a>\
+            <a href=\"\u{1}javascript:alert(1)\">d</a>\
+            <a href=\"&#106avascript:alert(1)\">e</a>\
+            <a href=\"tel:123\">f</a>\
+            <a href=\"mailto:a@example.com\">g</a>\
+            <a href=\"/help?q=a:b&amp;x=1\">h</a>";
+
+        assert_eq!(
+            sanitize(html),
+            "<a>a</a><a>b</a><a>c</a><a>d</a>\
+             <a href=\"&amp;#106avascript:alert(1)\">e</a><a>f</a>\
+             <a href=\"mailto:a@example.com\">g</a>\
+             <a href=\"/help?q=a:b&amp;x=1\">h</a>"
+        );
+    }
+
+    #[test]
+    fn links_cover_anchors_and_bare_urls_in_body_order() {
+        let html = "<p>Visit https://example.com/start. Then</p>\
+            <a href=\"https://example.com/verify?u=1&amp;t=abc\"><b>Verify</b> email</a>\
+            <a href=\"#top\">top</a><script>var u = \"https://cdn.example/x.js\";</script>\
+            <p>Or paste (https://example.com/manual)</p>";
+
+        let links = links(html);
+        let summary: Vec<_> = links
+            .iter()
+            .map(|link| (link.url.as_str(), link.text.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("https://example.com/start", None),
+                ("https://example.com/verify?u=1&t=abc", Some("Verify email")),
+                ("https://example.com/manual", None),
+            ]
+        );
+        assert_eq!(links[0].offset, 9);
+        assert!(html[links[1].offset..].starts_with("<a href"));
+    }
+
+    #[test]
+    fn link_selectors_pick_the_first_matching_web_link() {
+        let links = links(
+            r#"<a href="mailto:help@example.com">Verify by mail</a>
+            <a href="https://example.com/prefs">Preferences</a>
+            <a href="https://example.com/a?t=1">Activate Account</a>"#,
+        );
+        let url = |selector: LinkSelector| selector.select(&links).map(|link| link.url.as_str());
+
+        assert_eq!(
+            url(LinkSelector::Verification),
+            Some("https://example.com/a?t=1")
+        );
+        assert_eq!(url(LinkSelector::First), Some("https://example.com/prefs"));
+        let pref = LinkSelector::TextContains("PREF".into());
+        assert_eq!(url(pref), Some("https://example.com/prefs"));
+        let regex = regex::Regex::new(r"\?t=\d").unwrap();
+        assert_eq!(
+            url(LinkSelector::UrlMatches(regex)),
+            Some("https://example.com/a?t=1")
+        );
+        assert_eq!(url(LinkSelector::UrlContains("help".into())), None);
+    }
+}
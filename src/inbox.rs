@@ -0,0 +1,426 @@
+//! Per-address handle bundling a [`Client`] with one GuerrillaMail inbox.
+
+use crate::{Client, EmailDetails, INBOX_LIFETIME, Message, Result};
+#[cfg(feature = "tokio")]
+use crate::{Error, RetryPolicy};
+use std::fmt;
+#[cfg(any(feature = "tokio", target_arch = "wasm32"))]
+use std::ops::Deref;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+#[cfg(feature = "tokio")]
+use std::time::Duration;
+#[cfg(feature = "tokio")]
+use tokio::task::JoinHandle;
+
+/// How long before expiry [`Inbox::keep_alive`] extends the inbox.
+#[cfg(feature = "tokio")]
+const KEEP_ALIVE_MARGIN: Duration = Duration::from_secs(5 * 60);
+/// Back-off between failed extension attempts in [`Inbox::keep_alive`].
+#[cfg(feature = "tokio")]
+const KEEP_ALIVE_RETRY: Duration = Duration::from_secs(30);
+/// Extension attempts [`Inbox::keep_alive`] makes before giving up on a transient failure.
+#[cfg(feature = "tokio")]
+const KEEP_ALIVE_ATTEMPTS: u32 = 5;
+
+/// Handle to a single GuerrillaMail address.
+///
+/// Created by [`Client::create_inbox`] or [`Client::inbox`]. An [`Inbox`] owns a cheap clone of
+/// the [`Client`] plus the address it was created for, so its methods forward to the matching
+/// client calls without the address being passed around as a raw string.
+///
+/// The handle tracks when the address was created and last extended. Expiry state is shared
+/// between clones, so an extension made through one clone (or by [`keep_alive`](Inbox::keep_alive))
+/// is visible through all of them.
+///
+/// `Clone` is cheap; clones share the underlying client session.
+#[derive(Clone)]
+pub struct Inbox {
+    client: Client,
+    address: String,
+    created_at: u64,
+    expiry: Arc<Expiry>,
+}
+
+/// Extension state shared between clones of an [`Inbox`].
+#[derive(Debug)]
+struct Expiry {
+    /// Unix seconds of the last extension, or 0 if never extended.
+    extended_at: AtomicU64,
+    /// Unix seconds after which the address is expected to be dropped.
+    expires_at: AtomicU64,
+}
+
+impl fmt::Debug for Inbox {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Inbox")
+            .field("address", &self.address)
+            .field("created_at", &self.created_at)
+            .field("extended_at", &self.extended_at())
+            .field("expires_at", &self.expires_at())
+            .finish()
+    }
+}
+
+impl fmt::Display for Inbox {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.address)
+    }
+}
+
+impl Inbox {
+    /// Create a handle for an address set at `created_at` (Unix seconds).
+    pub(crate) fn new(client: Client, address: String, created_at: u64) -> Self {
+        Self {
+            client,
+            address,
+            created_at,
+            expiry: Arc::new(Expiry {
+                extended_at: AtomicU64::new(0),
+                expires_at: AtomicU64::new(created_at + INBOX_LIFETIME.as_secs()),
+            }),
+        }
+    }
+
+    /// Full email address (`alias@domain`).
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// Local-part of the address (everything before `@`).
+    pub fn alias(&self) -> &str {
+        self.address.split('@').next().unwrap_or(&self.address)
+    }
+
+    /// Domain part of the address (everything after `@`), or `""` if there is none.
+    pub fn domain(&self) -> &str {
+        self.address
+            .split_once('@')
+            .map_or("", |(_, domain)| domain)
+    }
+
+    /// Unix timestamp in seconds of when the address was set.
+    ///
+    /// For handles created with [`Client::inbox`] this is the time the handle was created.
+    pub fn created_at(&self) -> u64 {
+        self.created_at
+    }
+
+    /// Unix timestamp in seconds of the last successful [`extend`](Inbox::extend), if any.
+    pub fn extended_at(&self) -> Option<u64> {
+        match self.expiry.extended_at.load(Ordering::Acquire) {
+            0 => None,
+            ts => Some(ts),
+        }
+    }
+
+    /// Unix timestamp in seconds after which GuerrillaMail is expected to drop the address.
+    pub fn expires_at(&self) -> u64 {
+        self.expiry.expires_at.load(Ordering::Acquire)
+    }
+
+    /// The client this inbox issues requests through.
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    /// List messages currently in the inbox; see [`Client::get_messages`].
+    pub async fn messages(&self) -> Result<Vec<Message>> {
+        self.client.get_messages(&self.address).await
+    }
+
+    /// Fetch the full contents of a message; see [`Client::fetch_email`].
+    pub async fn fetch(&self, mail_id: &str) -> Result<EmailDetails> {
+        self.client.fetch_email(&self.address, mail_id).await
+    }
+
+    /// Forget the address for this session; see [`Client::delete_email`].
+    pub async fn delete(&self) -> Result<bool> {
+        self.client.delete_email(&self.address).await
+    }
+
+    /// Wrap this inbox in an [`InboxGuard`] that tries to forget the address when dropped.
+    #[cfg(any(feature = "tokio", target_arch = "wasm32"))]
+    pub fn guard(self) -> InboxGuard {
+        InboxGuard::new(self)
+    }
+
+    /// Extend the inbox lifetime and update [`expires_at`](Inbox::expires_at); see
+    /// [`Client::extend_session`].
+    pub async fn extend(&self) -> Result<u64> {
+        let expires_at = self.client.extend_session(&self.address).await?;
+        self.expiry
+            .extended_at
+            .store(self.client.unix_now(), Ordering::Release);
+        self.expiry.expires_at.store(expires_at, Ordering::Release);
+        Ok(expires_at)
+    }
+
+    /// Spawn a background task that extends the inbox shortly before it expires.
+    ///
+    /// The task sleeps until five minutes before [`expires_at`](Inbox::expires_at), calls
+    /// [`extend`](Inbox::extend), and repeats. Transient failures (network errors, 5xx,
+    /// throttling) are retried every 30 seconds, up to five attempts; any other error, such as
+    /// an expired session, stops the task at once. Waits use the client's
+    /// [`Timer`](crate::Timer). The task runs on the current Tokio runtime until it gives up or
+    /// the returned [`KeepAlive`] is stopped or dropped; see [`KeepAlive::failed`] to learn why
+    /// it gave up.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use guerrillamail_client::Client;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), guerrillamail_client::Error> {
+    /// let client = Client::new().await?;
+    /// let inbox = client.create_inbox("soak-test").await?;
+    /// let keep_alive = inbox.keep_alive();
+    /// // ... hours of polling `inbox.messages()` ...
+    /// if !keep_alive.is_running() {
+    ///     eprintln!("inbox lost: {}", keep_alive.failed().await);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub fn keep_alive(&self) -> KeepAlive {
+        let inbox = self.clone();
+        let task = tokio::spawn(async move {
+            loop {
+                let extend_at = inbox
+                    .expires_at()
+                    .saturating_sub(KEEP_ALIVE_MARGIN.as_secs());
+                let wait = extend_at.saturating_sub(inbox.client.unix_now());
+                inbox.client.sleep(Duration::from_secs(wait)).await;
+
+                let mut attempt = 1;
+                while let Err(err) = inbox.extend().await {
+                    if attempt == KEEP_ALIVE_ATTEMPTS || !is_transient(&err) {
+                        return err;
+                    }
+                    inbox.client.sleep(KEEP_ALIVE_RETRY).await;
+                    attempt += 1;
+                }
+            }
+        });
+        KeepAlive { task }
+    }
+}
+
+/// Whether a failed extension is worth retrying in [`Inbox::keep_alive`].
+#[cfg(feature = "tokio")]
+fn is_transient(err: &Error) -> bool {
+    matches!(err, Error::RateLimited { .. } | Error::CircuitOpen { .. })
+        || RetryPolicy::is_retryable(err)
+}
+
+/// Handle to the background task started by [`Inbox::keep_alive`].
+///
+/// Dropping the handle stops the task.
+#[cfg(feature = "tokio")]
+#[derive(Debug)]
+pub struct KeepAlive {
+    task: JoinHandle<Error>,
+}
+
+#[cfg(feature = "tokio")]
+impl KeepAlive {
+    /// Stop extending the inbox.
+    pub fn stop(self) {}
+
+    /// Whether the task is still keeping the inbox alive; `false` once it has given up.
+    pub fn is_running(&self) -> bool {
+        !self.task.is_finished()
+    }
+
+    /// Wait until the task gives up and return the error that made it stop.
+    ///
+    /// Never completes while extensions keep succeeding.
+    pub async fn failed(mut self) -> Error {
+        match (&mut self.task).await {
+            Ok(err) => err,
+            Err(err) => std::panic::resume_unwind(err.into_panic()),
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl Drop for KeepAlive {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// RAII guard that tries to forget its [`Inbox`] when dropped.
+///
+/// Deletion is best-effort. On drop, an armed guard spawns [`Inbox::delete`] onto the current
+/// Tokio runtime and returns immediately; nothing waits for the request and its result is
+/// discarded. The task does not survive runtime shutdown: a guard dropped at the end of
+/// `#[tokio::main]` or `#[tokio::test]` is cancelled with the runtime before the request goes
+/// out, and a guard dropped outside a runtime schedules nothing. On wasm32 the deletion is
+/// spawned on the JavaScript event loop instead. Needs the `tokio` feature on other targets.
+///
+/// For guaranteed cleanup, use [`Client::with_inbox`] or await [`Inbox::delete`] yourself. Call
+/// [`disarm`](InboxGuard::disarm) to keep the address and take the inbox back.
+///
+/// The guard dereferences to [`Inbox`], so inbox methods can be called on it directly.
+///
+/// # Example
+/// ```no_run
+/// # use guerrillamail_client::Client;
+/// # async fn serve_more_requests() {}
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), guerrillamail_client::Error> {
+/// let client = Client::new().await?;
+/// {
+///     let inbox = client.create_inbox("myalias").await?.guard();
+///     println!("Using {}", inbox.address());
+///     // `forget_me` is spawned when `inbox` goes out of scope here...
+/// }
+/// // ...and runs while the runtime keeps going.
+/// serve_more_requests().await;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(any(feature = "tokio", target_arch = "wasm32"))]
+#[derive(Debug)]
+pub struct InboxGuard {
+    inbox: Option<Inbox>,
+}
+
+#[cfg(any(feature = "tokio", target_arch = "wasm32"))]
+impl InboxGuard {
+    /// Arm a guard for `inbox`.
+    pub fn new(inbox: Inbox) -> Self {
+        Self { inbox: Some(inbox) }
+    }
+
+    /// Cancel the scheduled deletion and return the inbox.
+    pub fn disarm(mut self) -> Inbox {
+        self.inbox.take().expect("inbox guard already disarmed")
+    }
+}
+
+#[cfg(any(feature = "tokio", target_arch = "wasm32"))]
+impl Deref for InboxGuard {
+    type Target = Inbox;
+
+    fn deref(&self) -> &Inbox {
+        self.inbox.as_ref().expect("inbox guard already disarmed")
+    }
+}
+
+#[cfg(any(feature = "tokio", target_arch = "wasm32"))]
+impl Drop for InboxGuard {
+    fn drop(&mut self) {
+        let Some(inbox) = self.inbox.take() else {
+            return;
+        };
+        #[cfg(feature = "tokio")]
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            handle.spawn(async move {
+                let _ = inbox.delete().await;
+            });
+        }
+        #[cfg(target_arch = "wasm32")]
+        wasm_bindgen_futures::spawn_local(async move {
+            let _ = inbox.delete().await;
+        });
+    }
+}
+
+#[cfg(all(test, feature = "tokio"))]
+mod tests {
+    use super::*;
+    use crate::client::session_client;
+    use httpmock::Method::GET;
+    use httpmock::MockServer;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn keep_alive_extends_inbox_close_to_expiry() {
+        let server = MockServer::start();
+        let base_url = server.base_url();
+        let now = crate::clock::since_epoch(&crate::SystemClock).as_secs();
+
+        let extend_mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/ajax.php")
+                .query_param("f", "extend")
+                .query_param("in", "alias");
+            then.status(200)
+                .json_body(json!({ "email_timestamp": now }));
+        });
+
+        let client = Client::new_for_tests(base_url.clone(), format!("{base_url}/ajax.php"));
+        let inbox = Inbox::new(client, "alias@example.com".to_string(), 1);
+        assert_eq!(inbox.expires_at(), 1 + INBOX_LIFETIME.as_secs());
+        assert!(inbox.extended_at().is_none());
+
+        let keep_alive = inbox.keep_alive();
+        for _ in 0..100 {
+            if inbox.extended_at().is_some() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        keep_alive.stop();
+
+        assert_eq!(inbox.expires_at(), now + INBOX_LIFETIME.as_secs());
+        assert!(inbox.extended_at().is_some());
+        extend_mock.assert_hits(1);
+    }
+
+    /// Completes every sleep at once.
+    struct Immediate;
+
+    impl crate::Timer for Immediate {
+        fn sleep(&self, _duration: Duration) -> crate::SleepFuture {
+            Box::pin(std::future::ready(()))
+        }
+    }
+
+    async fn expired_inbox(server: &MockServer) -> Inbox {
+        let base_url = server.base_url();
+        let client = session_client(&base_url)
+            .timer(Immediate)
+            .build()
+            .await
+            .unwrap();
+        Inbox::new(client, "alias@example.com".to_string(), 1)
+    }
+
+    #[tokio::test]
+    async fn keep_alive_gives_up_after_repeated_transient_failures() {
+        let server = MockServer::start();
+        let extend_mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/ajax.php")
+                .query_param("f", "extend");
+            then.status(502);
+        });
+
+        let keep_alive = expired_inbox(&server).await.keep_alive();
+        let err = keep_alive.failed().await;
+
+        assert!(matches!(err, Error::Api { status, .. } if status.as_u16() == 502));
+        extend_mock.assert_hits(KEEP_ALIVE_ATTEMPTS as usize);
+    }
+
+    #[tokio::test]
+    async fn keep_alive_stops_at_once_on_permanent_failure() {
+        let server = MockServer::start();
+        let extend_mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/ajax.php")
+                .query_param("f", "extend");
+            then.status(200)
+                .json_body(json!({ "error": "session expired" }));
+        });
+
+        let keep_alive = expired_inbox(&server).await.keep_alive();
+        let err = keep_alive.failed().await;
+
+        assert!(matches!(err, Error::ResponseParse { .. }));
+        extend_mock.assert_hits(1);
+    }
+}
@@ -0,0 +1,139 @@
+//! Cookie jar for wasm32, where reqwest leaves cookies to the browser and has none of its own.
+
+use reqwest::Url;
+use reqwest::header::HeaderValue;
+use std::sync::Mutex;
+
+/// Stands in for `reqwest::cookie::Jar`, with just the parts the client uses.
+///
+/// Cookies are kept per domain and name. `Domain` and `Max-Age` are honoured; `Path`, `Secure`,
+/// and `Expires` are ignored, which is enough for the GuerrillaMail session cookie.
+#[derive(Debug, Default)]
+pub(crate) struct Jar {
+    cookies: Mutex<Vec<Cookie>>,
+}
+
+#[derive(Debug)]
+struct Cookie {
+    domain: String,
+    host_only: bool,
+    name: String,
+    value: String,
+}
+
+impl Jar {
+    /// Store a cookie in `Set-Cookie` syntax as if `url` had sent it.
+    pub(crate) fn add_cookie_str(&self, cookie: &str, url: &Url) {
+        let Some(host) = url.host_str().map(str::to_ascii_lowercase) else {
+            return;
+        };
+        let mut parts = cookie.split(';').map(str::trim);
+        let Some((name, value)) = parts.next().and_then(|pair| pair.split_once('=')) else {
+            return;
+        };
+        let (name, value) = (name.trim(), value.trim());
+        if name.is_empty() {
+            return;
+        }
+
+        let mut domain = None;
+        let mut expired = false;
+        for part in parts {
+            let (key, value) = part.split_once('=').unwrap_or((part, ""));
+            if key.trim().eq_ignore_ascii_case("domain") {
+                domain = Some(value.trim().trim_start_matches('.').to_ascii_lowercase());
+            } else if key.trim().eq_ignore_ascii_case("max-age") {
+                expired = value.trim().parse::<i64>().is_ok_and(|age| age <= 0);
+            }
+        }
+        let (domain, host_only) = match domain {
+            Some(domain) if !domain_matches(&host, &domain) => return,
+            Some(domain) => (domain, false),
+            None => (host, true),
+        };
+
+        let mut cookies = self.cookies.lock().expect("cookie jar poisoned");
+        cookies.retain(|cookie| cookie.domain != domain || cookie.name != name);
+        if !expired {
+            cookies.push(Cookie {
+                domain,
+                host_only,
+                name: name.to_string(),
+                value: value.to_string(),
+            });
+        }
+    }
+}
+
+/// Mirrors `reqwest::cookie::CookieStore`, so the client uses either jar the same way.
+pub(crate) trait CookieStore {
+    /// Store the `Set-Cookie` headers of a response from `url`.
+    fn set_cookies(&self, headers: &mut dyn Iterator<Item = &HeaderValue>, url: &Url);
+
+    /// The `Cookie` header to send to `url`, if any cookie applies.
+    fn cookies(&self, url: &Url) -> Option<HeaderValue>;
+}
+
+impl CookieStore for Jar {
+    fn set_cookies(&self, headers: &mut dyn Iterator<Item = &HeaderValue>, url: &Url) {
+        for header in headers {
+            if let Ok(cookie) = header.to_str() {
+                self.add_cookie_str(cookie, url);
+            }
+        }
+    }
+
+    fn cookies(&self, url: &Url) -> Option<HeaderValue> {
+        let host = url.host_str()?.to_ascii_lowercase();
+        let cookies = self.cookies.lock().expect("cookie jar poisoned");
+        let header = cookies
+            .iter()
+            .filter(|cookie| match cookie.host_only {
+                true => cookie.domain == host,
+                false => domain_matches(&host, &cookie.domain),
+            })
+            .map(|cookie| format!("{}={}", cookie.name, cookie.value))
+            .collect::<Vec<_>>()
+            .join("; ");
+        (!header.is_empty()).then(|| HeaderValue::from_str(&header).ok())?
+    }
+}
+
+/// Whether `host` is `domain` or one of its subdomains.
+fn domain_matches(host: &str, domain: &str) -> bool {
+    host.strip_suffix(domain)
+        .is_some_and(|prefix| prefix.is_empty() || prefix.ends_with('.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sends_cookies_back_to_matching_hosts() {
+        let jar = Jar::default();
+        let site = Url::parse("https://www.guerrillamail.com/ajax.php").unwrap();
+        let headers = [
+            HeaderValue::from_static("PHPSESSID=abc; path=/"),
+            HeaderValue::from_static("lang=en; Domain=.guerrillamail.com"),
+        ];
+        jar.set_cookies(&mut headers.iter(), &site);
+
+        let cookies = |url: &str| jar.cookies(&Url::parse(url).unwrap());
+        assert_eq!(
+            cookies("https://www.guerrillamail.com/").unwrap(),
+            "PHPSESSID=abc; lang=en"
+        );
+        assert_eq!(
+            cookies("https://api.guerrillamail.com/").unwrap(),
+            "lang=en"
+        );
+        assert_eq!(cookies("https://notguerrillamail.com/"), None);
+
+        jar.add_cookie_str("PHPSESSID=; Max-Age=0", &site);
+        assert_eq!(
+            cookies("https://www.guerrillamail.com/").unwrap(),
+            "lang=en"
+        );
+    }
+}
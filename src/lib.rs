@@ -5,13 +5,52 @@
 //! For Rust developers who need throwaway addresses in integration tests, demos, or automation scripts without running mail infrastructure: configure with [`ClientBuilder`], obtain an address, poll for messages ([`Message`]), then discard the inbox when done.
 //!
 //! ## Runtime requirements
-//! Async-only; run inside a Tokio (v1) runtime. HTTP calls use `reqwest`, so ensure the chosen Tokio features (`rt-multi-thread` or `current_thread`) are available in your application.
+//! Async-only. By default requests are sent with `reqwest` and waits use Tokio's timer (`TokioTimer`), so the default setup runs inside a Tokio (v1) runtime, with either the `rt-multi-thread` or `current_thread` flavor.
+//!
+//! Neither default is a requirement of the request methods: with a custom [`Transport`] and [`Timer`] (see `ClientBuilder::transport` and `ClientBuilder::timer`) they run on any executor, such as async-std or smol. Background helpers (`Inbox::keep_alive`, `InboxGuard`, watchers, `InboxPool`) spawn Tokio tasks, always need Tokio, and live behind the default `tokio` feature; without it only Tokio's timer, sync, and DNS pieces are compiled in.
+//!
+//! ## WebAssembly
+//! On `wasm32-unknown-unknown` (build with `default-features = false`) requests go through reqwest's fetch backend and waits use JavaScript timers (`JsTimer`), so no Tokio runtime is involved; drive the futures with `wasm-bindgen-futures`. The client keeps its own cookie jar there, since fetch has none, and applies the timeout per request. The browser decides routing and TLS, so proxies are rejected at build time and the TLS, DNS, source-address, and HTTP-version options of [`ClientBuilder`] do not exist, nor do raw response capture and request IDs on errors. `Inbox::keep_alive`, watchers, `InboxPool`, and the `blocking`, `cli`, `har`, `testing`, `tor`, and `vcr` features need native threads or sockets and are unavailable.
+//!
+//! ## TLS backends
+//! The `native-tls` feature (default) uses the platform TLS library. Disable default features and enable `rustls` for a pure-Rust stack, e.g. for musl builds. If both are enabled, `rustls` is used.
+//!
+//! Certificates are always validated. `ClientBuilder::danger_accept_invalid_certs` exists only with the opt-in `insecure-tls` feature.
+//!
+//! ## Tor
+//! The opt-in `tor` feature adds `ClientBuilder::tor`, which routes traffic through a local Tor SOCKS port (`127.0.0.1:9050`), and `Client::create_isolated_inbox`, which puts each inbox on its own circuit via SOCKS isolation credentials.
+//!
+//! ## Tower
+//! The opt-in `tower` feature implements `tower::Service<ApiRequest>` for [`Client`], so raw AJAX calls can be wrapped in standard tower layers (timeout, retry, rate limiting, load shedding).
+//!
+//! ## Blocking
+//! The opt-in `blocking` feature adds [`blocking::Client`], a synchronous wrapper for scripts and build tooling that do not run an async runtime.
+//!
+//! ## HAR recording
+//! The opt-in `har` feature adds `HarRecorder` and `ClientBuilder::record_har`, which capture every request and response (tokens and cookies redacted) into a HAR file for inspection in browser devtools.
+//!
+//! ## Tracing
+//! The opt-in `tracing` feature wraps every HTTP exchange in a `guerrillamail.request` span (`DEBUG` level) with the AJAX `function`, the inbox `alias`, the `method`, and the response `status` and `elapsed_ms`. Tokens, cookies, and headers are never recorded. `ClientBuilder::log_requests` adds one structured record per exchange on the `guerrillamail::http` target, and the `log` feature forwards all of it to the `log` crate.
+//!
+//! ## Metrics
+//! [`ClientBuilder::metrics`] reports every HTTP exchange (endpoint, status, latency) to a [`MetricsSink`], for request counters, error rates, and latency histograms in your own metrics system.
+//!
+//! ## Timestamps
+//! The opt-in `chrono` feature adds `Message::received_at` and `EmailDetails::received_at`, which return the receive time as a `chrono::DateTime<Utc>`; the raw `mail_timestamp` and `mail_date` strings stay available.
+//!
+//! ## MIME decoding
+//! The opt-in `mime` feature decodes RFC 2047 encoded-words (`=?UTF-8?B?...?=`) in `mail_from` and `mail_subject` as messages are deserialized. It also adds `EmailDetails::decoded_body`, which undoes quoted-printable or base64 transfer encodings left on `mail_body` and transcodes it from its declared charset (the field itself is kept as received), and the [`mime`] module with the decoders.
+//!
+//! ## Offline tests
+//! The opt-in `vcr` feature adds [`Cassette`], a transport that records GuerrillaMail responses to a fixture file once and replays them in CI without network access.
+//!
+//! The opt-in `testing` feature adds the [`testing`] module: a local mock of `ajax.php` with canned responses, and a stateful in-process fake whose inboxes can be filled with messages, for integration-testing code built on this crate.
 //!
 //! ## Out of scope
 //! Not a general-purpose mail client, SMTP sender, or durable mailbox. It only proxies the GuerrillaMail service and inherits its availability, spam filtering, and retention limits.
 //!
 //! ## Errors
-//! All network calls surface transport and non-2xx statuses as [`Error::Request`]; shape or content issues become [`Error::ResponseParse`] or [`Error::Json`]. The crate-wide [`Result`] alias wraps these errors.
+//! All network calls surface transport failures as [`Error::Request`] and non-2xx statuses as [`Error::Api`]; shape or content issues become [`Error::ResponseParse`], [`Error::Deserialize`] (with the offending field path and the start of the body), or [`Error::InvalidMessages`]. Malformed addresses are rejected with [`Error::InvalidAddress`] before anything is sent; [`EmailAddress`] validates them up front and can be passed wherever an address `&str` is expected. The crate-wide [`Result`] alias wraps these errors.
 //!
 //! ## Example
 //! ```no_run
@@ -33,13 +72,118 @@
 //! }
 //! ```
 
+#[cfg(not(any(feature = "native-tls", feature = "rustls", target_arch = "wasm32")))]
+compile_error!("enable a TLS backend: the `native-tls` (default) or `rustls` feature");
+
+#[cfg(all(
+    target_arch = "wasm32",
+    any(
+        feature = "blocking",
+        feature = "cli",
+        feature = "har",
+        feature = "testing",
+        feature = "tokio",
+        feature = "tor",
+        feature = "vcr"
+    )
+))]
+compile_error!(
+    "the `blocking`, `cli`, `har`, `testing`, `tokio`, `tor`, and `vcr` features are not available on wasm32"
+);
+
+mod address;
+pub mod alias;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+#[cfg(feature = "vcr")]
+mod cassette;
+mod circuit;
 mod client;
+mod clock;
 mod error;
+#[cfg(feature = "har")]
+mod har;
+mod html;
+mod inbox;
+#[cfg(any(target_arch = "wasm32", test))]
+mod jar;
+mod metrics;
+mod middleware;
+#[cfg(feature = "mime")]
+pub mod mime;
 mod models;
+mod otp;
+#[cfg(feature = "tokio")]
+mod pool;
+mod profile;
+mod proxy;
+mod rate_limit;
+#[cfg(not(target_arch = "wasm32"))]
+mod resolve;
+mod retry;
+mod runtime;
+mod search;
+#[cfg(feature = "tower")]
+mod service;
+mod session;
+mod telemetry;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod transport;
+#[cfg(feature = "tokio")]
+mod watch;
+
+pub use address::{Assigned, EmailAddress};
+pub use alias::AliasGenerator;
+#[cfg(feature = "vcr")]
+pub use cassette::Cassette;
+pub use client::{Client, ClientBuilder, INBOX_LIFETIME, RawResponse, UserAgentRotation};
+#[cfg(not(target_arch = "wasm32"))]
+pub use clock::TokioClock;
+pub use clock::{Clock, SystemClock};
+pub use error::{EntryFailure, Error};
+#[cfg(feature = "har")]
+pub use har::HarRecorder;
+pub use html::{ExtractedLink, LinkSelector, LinkVisit};
+pub use inbox::Inbox;
+#[cfg(any(feature = "tokio", target_arch = "wasm32"))]
+pub use inbox::InboxGuard;
+#[cfg(feature = "tokio")]
+pub use inbox::KeepAlive;
+pub use metrics::{MetricsSink, RequestMetrics};
+pub use middleware::RequestInterceptor;
+pub use models::{Attachment, CurrentAddress, EmailDetails, InboxSnapshot, Message};
+pub use otp::CodePattern;
+#[cfg(feature = "tokio")]
+pub use pool::{InboxPool, InboxPoolBuilder};
+pub use profile::HeaderProfile;
+pub use proxy::{ProxyConfig, RotationStrategy};
+#[cfg(not(target_arch = "wasm32"))]
+pub use resolve::IpPreference;
+pub use retry::RetryPolicy;
+#[cfg(target_arch = "wasm32")]
+pub use runtime::JsTimer;
+#[cfg(not(target_arch = "wasm32"))]
+pub use runtime::TokioTimer;
+pub use runtime::{SleepFuture, Timer};
+pub use search::{Query, SearchHit};
+#[cfg(feature = "tower")]
+pub use service::ApiRequest;
+pub use session::Session;
+pub use transport::{Transport, TransportFuture};
+#[cfg(feature = "tokio")]
+pub use watch::{MultiWatcher, WatchEvent, WatchStream};
+
+/// Token for cooperatively stopping watchers and pools; re-exported from `tokio-util`.
+pub use tokio_util::sync::CancellationToken;
+
+/// X.509 certificate for [`ClientBuilder::add_root_certificate`]; re-exported from `reqwest`.
+#[cfg(not(target_arch = "wasm32"))]
+pub use reqwest::Certificate;
 
-pub use client::{Client, ClientBuilder};
-pub use error::Error;
-pub use models::{Attachment, EmailDetails, Message};
+/// DNS resolver types for [`ClientBuilder::dns_resolver`]; re-exported from `reqwest`.
+#[cfg(not(target_arch = "wasm32"))]
+pub use reqwest::dns;
 
 /// Result type alias for GuerrillaMail operations.
 ///
@@ -0,0 +1,137 @@
+//! Per-request metrics hooks.
+
+use crate::Error;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// One completed HTTP exchange, as reported to a [`MetricsSink`].
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub struct RequestMetrics<'a> {
+    /// Client-generated ID of this exchange, as in its `tracing` span and in `Error::Api`.
+    pub request_id: &'a str,
+    /// The AJAX function (`check_email`, `set_email_user`, ...), or the URL path for page and
+    /// attachment requests (`/`, `/inbox`).
+    pub endpoint: &'a str,
+    /// HTTP status of the response, or `None` if no response was received.
+    pub status: Option<reqwest::StatusCode>,
+    /// Time from sending the request to receiving the response headers (or the error).
+    pub elapsed: Duration,
+    /// Why no response was received, when `status` is `None`.
+    pub error: Option<&'a Error>,
+}
+
+impl RequestMetrics<'_> {
+    /// Whether the exchange failed: no response, or a non-success status.
+    pub fn is_error(&self) -> bool {
+        self.status.is_none_or(|status| !status.is_success())
+    }
+}
+
+/// Receives an observation for every HTTP exchange of a [`Client`], for
+/// [`ClientBuilder::metrics`].
+///
+/// Called once per request actually sent, including bootstrap, retries, and
+/// re-authentication, after the response headers arrive. Count requests and errors per
+/// [`endpoint`](RequestMetrics::endpoint) and record [`elapsed`](RequestMetrics::elapsed) in a
+/// histogram to chart GuerrillaMail health over a long test campaign. The call is synchronous
+/// and on the request path: keep it cheap, e.g. atomic counters or a channel send.
+///
+/// `Arc<S>` implements the trait for any sink `S`, so a sink can be shared with the code that
+/// reads it.
+///
+/// [`Client`]: crate::Client
+/// [`ClientBuilder::metrics`]: crate::ClientBuilder::metrics
+///
+/// # Example
+/// ```
+/// use guerrillamail_client::{MetricsSink, RequestMetrics};
+/// use std::collections::HashMap;
+/// use std::sync::Mutex;
+/// use std::time::Duration;
+///
+/// /// Request count, error count, and total latency per endpoint.
+/// #[derive(Default)]
+/// struct Counters(Mutex<HashMap<String, (u64, u64, Duration)>>);
+///
+/// impl MetricsSink for Counters {
+///     fn observe(&self, request: &RequestMetrics<'_>) {
+///         let mut counters = self.0.lock().unwrap();
+///         let entry = counters.entry(request.endpoint.to_string()).or_default();
+///         entry.0 += 1;
+///         entry.1 += u64::from(request.is_error());
+///         entry.2 += request.elapsed;
+///     }
+/// }
+/// ```
+pub trait MetricsSink: Send + Sync {
+    /// Record one completed exchange.
+    fn observe(&self, request: &RequestMetrics<'_>);
+}
+
+impl<S: MetricsSink + ?Sized> MetricsSink for Arc<S> {
+    fn observe(&self, request: &RequestMetrics<'_>) {
+        (**self).observe(request);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::session_client;
+    use httpmock::Method::GET;
+    use httpmock::MockServer;
+    use serde_json::json;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Recorded(Mutex<Vec<(String, Option<u16>)>>);
+
+    impl MetricsSink for Recorded {
+        fn observe(&self, request: &RequestMetrics<'_>) {
+            let status = request.status.map(|status| status.as_u16());
+            self.0
+                .lock()
+                .unwrap()
+                .push((request.endpoint.to_string(), status));
+        }
+    }
+
+    #[tokio::test]
+    async fn observes_each_exchange_per_endpoint() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET)
+                .path("/ajax.php")
+                .query_param("f", "check_email");
+            then.status(200).json_body(json!({ "list": [] }));
+        });
+        server.mock(|when, then| {
+            when.method(GET)
+                .path("/ajax.php")
+                .query_param("f", "fetch_email");
+            then.status(500).body("down");
+        });
+        let recorded = Arc::new(Recorded::default());
+        let client = session_client(&server.base_url())
+            .metrics(Arc::clone(&recorded))
+            .build()
+            .await
+            .unwrap();
+
+        client.get_messages("qa@sharklasers.com").await.unwrap();
+        client
+            .fetch_email("qa@sharklasers.com", "1")
+            .await
+            .unwrap_err();
+
+        let recorded = recorded.0.lock().unwrap();
+        assert_eq!(
+            *recorded,
+            [
+                ("check_email".to_string(), Some(200)),
+                ("fetch_email".to_string(), Some(500)),
+            ]
+        );
+    }
+}
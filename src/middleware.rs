@@ -0,0 +1,212 @@
+//! Request interceptors.
+
+use crate::Result;
+#[cfg(feature = "har")]
+use crate::har::{HarRecorder, SentRequest};
+use crate::metrics::{MetricsSink, RequestMetrics};
+use crate::telemetry::{RequestId, RequestInfo};
+#[cfg(feature = "har")]
+use crate::transport::buffer;
+use crate::transport::{Transport, TransportFuture};
+use reqwest::Url;
+use std::sync::Arc;
+use web_time::Instant;
+
+/// Hook into every HTTP exchange of a [`Client`], for [`ClientBuilder::with_middleware`].
+///
+/// Interceptors see each request after the client has added its headers and before it is sent,
+/// including bootstrap, re-authentication, and retried requests, and see each outcome before
+/// the client interprets it. Use them for auth injection, logging, or chaos testing. Both
+/// methods default to doing nothing, and both are synchronous: keep them cheap.
+///
+/// With several interceptors, `on_request` runs in registration order and `on_response` in
+/// reverse.
+///
+/// [`Client`]: crate::Client
+/// [`ClientBuilder::with_middleware`]: crate::ClientBuilder::with_middleware
+///
+/// # Example
+/// ```
+/// use guerrillamail_client::{Error, RequestInterceptor};
+///
+/// /// Tags requests and fails one in ten before it is sent.
+/// struct Chaos;
+///
+/// impl RequestInterceptor for Chaos {
+///     fn on_request(&self, request: &mut reqwest::Request) -> Result<(), Error> {
+///         request.headers_mut().insert("x-test-run", "42".parse().unwrap());
+///         if rand::random_ratio(1, 10) {
+///             return Err(Error::ResponseParse {
+///                 reason: "injected failure",
+///                 request_id: None,
+///             });
+///         }
+///         Ok(())
+///     }
+/// }
+/// ```
+pub trait RequestInterceptor: Send + Sync {
+    /// Inspect or modify `request` before it is sent.
+    ///
+    /// Returning an error aborts the request; the error is returned to the caller (and counts
+    /// towards retries and the circuit breaker like any other).
+    fn on_request(&self, request: &mut reqwest::Request) -> Result<()> {
+        let _ = request;
+        Ok(())
+    }
+
+    /// Inspect the response, or the error if none was received.
+    fn on_response(&self, response: &Result<reqwest::Response>) {
+        let _ = response;
+    }
+}
+
+/// A transport that runs interceptors around another transport.
+pub(crate) struct Intercepted<'a> {
+    pub(crate) inner: &'a dyn Transport,
+    pub(crate) interceptors: &'a [Arc<dyn RequestInterceptor>],
+    /// Records each exchange as sent, after the interceptors have run.
+    #[cfg(feature = "har")]
+    pub(crate) har: Option<&'a HarRecorder>,
+    /// Observes each exchange, after the interceptors have run.
+    pub(crate) metrics: Option<&'a dyn MetricsSink>,
+    /// Emit a `guerrillamail::http` record for each exchange.
+    #[cfg(feature = "tracing")]
+    pub(crate) log_requests: bool,
+}
+
+impl Transport for Intercepted<'_> {
+    fn send(&self, mut request: reqwest::Request) -> TransportFuture<'_> {
+        Box::pin(async move {
+            for interceptor in self.interceptors {
+                interceptor.on_request(&mut request)?;
+            }
+            let id = RequestId::generate();
+            let describe = cfg!(feature = "tracing") || self.metrics.is_some();
+            let info = describe.then(|| RequestInfo::new(id.clone(), &request));
+            let started = Instant::now();
+            let exchange = async move {
+                #[cfg(feature = "har")]
+                if let Some(har) = self.har {
+                    return record(har, self.inner, request).await;
+                }
+                self.inner.send(request).await
+            };
+            #[cfg(feature = "tracing")]
+            let response = match &info {
+                Some(info) => info.trace(exchange).await,
+                None => exchange.await,
+            };
+            #[cfg(not(feature = "tracing"))]
+            let response = exchange.await;
+            #[cfg(not(target_arch = "wasm32"))]
+            let response = match response {
+                Ok(mut response) => {
+                    response.extensions_mut().insert(id);
+                    Ok(response)
+                }
+                Err(err) => Err(err.with_request_id(Some(id.0))),
+            };
+            #[cfg(feature = "tracing")]
+            if let (true, Some(info)) = (self.log_requests, &info) {
+                info.log(&response, started.elapsed());
+            }
+            if let (Some(metrics), Some(info)) = (self.metrics, &info) {
+                metrics.observe(&RequestMetrics {
+                    request_id: &info.id.0,
+                    endpoint: &info.function,
+                    status: response.as_ref().ok().map(reqwest::Response::status),
+                    elapsed: started.elapsed(),
+                    error: response.as_ref().err(),
+                });
+            }
+            for interceptor in self.interceptors.iter().rev() {
+                interceptor.on_response(&response);
+            }
+            response
+        })
+    }
+}
+
+/// Send `request` through `inner`, buffering the response so it can be recorded to `har`.
+#[cfg(feature = "har")]
+async fn record(
+    har: &HarRecorder,
+    inner: &dyn Transport,
+    request: reqwest::Request,
+) -> Result<reqwest::Response> {
+    let sent = SentRequest::new(&request);
+    let response = match inner.send(request).await {
+        Ok(response) => buffer(response).await,
+        Err(err) => Err(err),
+    };
+    har.record(sent, response.as_ref());
+    response.map(reqwest::Response::from)
+}
+
+/// Callback registered with [`ClientBuilder::on_request`](crate::ClientBuilder::on_request).
+pub(crate) type AuditFn = dyn Fn(&reqwest::Method, &Url, &[(String, String)]) + Send + Sync;
+
+/// Interceptor that reports each request's method, URL, and parameters to an [`AuditFn`].
+pub(crate) struct Audit(pub(crate) Box<AuditFn>);
+
+impl RequestInterceptor for Audit {
+    fn on_request(&self, request: &mut reqwest::Request) -> Result<()> {
+        let mut url = request.url().clone();
+        url.set_query(None);
+        let params = redact(request_pairs(request));
+        (self.0)(request.method(), &url, &params);
+        Ok(())
+    }
+}
+
+/// Query parameters of `request`'s URL followed by the fields of its form body, if any.
+pub(crate) fn request_pairs(request: &reqwest::Request) -> Vec<(String, String)> {
+    let mut pairs = query_pairs(request.url());
+    if let Some(form) = request.body().and_then(|body| body.as_bytes()) {
+        pairs.extend(form_pairs(form));
+    }
+    pairs
+}
+
+/// Decode an `application/x-www-form-urlencoded` body with the URL query parser.
+pub(crate) fn form_pairs(form: &[u8]) -> Vec<(String, String)> {
+    let mut url = Url::parse("http://form.invalid/").expect("static url is valid");
+    url.set_query(std::str::from_utf8(form).ok());
+    query_pairs(&url)
+}
+
+/// Query parameters of `url`, decoded.
+pub(crate) fn query_pairs(url: &Url) -> Vec<(String, String)> {
+    url.query_pairs()
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect()
+}
+
+/// `pairs` with the values of token-like keys replaced by `***`.
+pub(crate) fn redact(mut pairs: Vec<(String, String)>) -> Vec<(String, String)> {
+    for (key, value) in &mut pairs {
+        if key.to_ascii_lowercase().contains("token") {
+            *value = "***".to_string();
+        }
+    }
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn form_pairs_decode_and_redact_tokens() {
+        let pairs = redact(form_pairs(b"email_user=a+b%40c&sid_token=secret"));
+
+        assert_eq!(
+            pairs,
+            [
+                ("email_user".to_string(), "a b@c".to_string()),
+                ("sid_token".to_string(), "***".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,341 @@
+//! Decoding of MIME encodings that GuerrillaMail passes through undecoded.
+//!
+//! Only available with the `mime` cargo feature, which also decodes the `mail_from` and
+//! `mail_subject` fields of [`Message`](crate::Message) and
+//! [`EmailDetails`](crate::EmailDetails) while they are deserialized, and adds
+//! [`EmailDetails::decoded_body`](crate::EmailDetails::decoded_body). `mail_body` itself is
+//! always kept as received.
+
+use base64::Engine;
+use base64::engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig};
+use std::borrow::Cow;
+
+/// Base64 as used in MIME: standard alphabet, padding optional.
+const BASE64: GeneralPurpose = GeneralPurpose::new(
+    &base64::alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// Decode RFC 2047 encoded-words (`=?UTF-8?B?...?=`) in a header value.
+///
+/// Both the `B` (base64) and `Q` (quoted-printable) encodings are supported in any charset
+/// known to the WHATWG Encoding Standard. Whitespace between adjacent encoded-words is dropped,
+/// as the RFC requires. Malformed words and words in unknown charsets are left as they are.
+///
+/// # Example
+/// ```
+/// use guerrillamail_client::mime::decode_header;
+///
+/// let subject = decode_header("=?UTF-8?B?R3LDvMOfZQ==?= aus =?ISO-8859-1?Q?K=F6ln?=");
+/// assert_eq!(subject, "Grüße aus Köln");
+/// assert_eq!(decode_header("plain text"), "plain text");
+/// ```
+pub fn decode_header(value: &str) -> Cow<'_, str> {
+    if !value.contains("=?") {
+        return Cow::Borrowed(value);
+    }
+
+    let mut decoded = String::with_capacity(value.len());
+    let mut rest = value;
+    let mut after_word = false;
+    while let Some(start) = rest.find("=?") {
+        let (before, candidate) = rest.split_at(start);
+        match decode_word(candidate) {
+            Some((text, len)) => {
+                if !(after_word && before.trim().is_empty()) {
+                    decoded.push_str(before);
+                }
+                decoded.push_str(&text);
+                rest = &candidate[len..];
+                after_word = true;
+            }
+            None => {
+                decoded.push_str(before);
+                decoded.push_str("=?");
+                rest = &candidate[2..];
+                after_word = false;
+            }
+        }
+    }
+    decoded.push_str(rest);
+    Cow::Owned(decoded)
+}
+
+/// Shortest body (in non-whitespace characters) that is taken to be base64.
+const MIN_BASE64_BODY: usize = 16;
+
+/// Undo a quoted-printable or base64 transfer encoding left on a message body.
+///
+/// A body counts as base64 when it consists only of base64 characters and line breaks and
+/// decodes to UTF-8 text, or to text that declares its charset. It counts as quoted-printable
+/// when every `=` starts a soft line break or a `=XX` escape, and at least one soft line break,
+/// `=3D`, or `=20` occurs; an escape such as `=de` alone is not enough, since URLs like
+/// `?token=deadbeef` look the same. Anything else is returned unchanged, so ordinary HTML
+/// (`<a href="...">`) is never touched. Decoded bytes that are not UTF-8 are kept
+/// one byte per character, for [`decode_body`] to transcode from the declared charset.
+///
+/// # Example
+/// ```
+/// use guerrillamail_client::mime::decode_transfer_encoding;
+///
+/// let body = decode_transfer_encoding("<p class=3D\"x\">Gr=C3=BC=\n=C3=9Fe</p>");
+/// assert_eq!(body, "<p class=\"x\">Grüße</p>");
+/// assert_eq!(decode_transfer_encoding("PGI+SGk8L2I+IHRoZXJlIQ=="), "<b>Hi</b> there!");
+/// ```
+pub fn decode_transfer_encoding(body: &str) -> Cow<'_, str> {
+    match decode_base64_body(body).or_else(|| decode_quoted_printable(body)) {
+        Some(bytes) => Cow::Owned(match String::from_utf8(bytes) {
+            Ok(text) => text,
+            Err(err) => err.into_bytes().into_iter().map(char::from).collect(),
+        }),
+        None => Cow::Borrowed(body),
+    }
+}
+
+/// Decode `body` if it is entirely base64 that decodes to UTF-8 or charset-declaring text.
+fn decode_base64_body(body: &str) -> Option<Vec<u8>> {
+    let compact: String = body.split_ascii_whitespace().collect();
+    let is_base64 = |c: char| c.is_ascii_alphanumeric() || matches!(c, '+' | '/' | '=');
+    let lines_ok = body.lines().all(|line| !line.trim().contains(' '));
+    if compact.len() < MIN_BASE64_BODY || !lines_ok || !compact.chars().all(is_base64) {
+        return None;
+    }
+    let bytes = BASE64.decode(compact).ok()?;
+    let is_text = |b: &u8| !b.is_ascii_control() || b.is_ascii_whitespace();
+    if !bytes.iter().all(is_text) {
+        return None;
+    }
+    let readable = std::str::from_utf8(&bytes).is_ok()
+        || declared_charset(&String::from_utf8_lossy(&bytes)).is_some();
+    readable.then_some(bytes)
+}
+
+/// Decode `body` if it is quoted-printable, per RFC 2045, with at least one soft line break or
+/// an escaped `=` or space to show for it.
+fn decode_quoted_printable(body: &str) -> Option<Vec<u8>> {
+    let input = body.as_bytes();
+    let mut bytes = Vec::with_capacity(input.len());
+    let mut evidence = false;
+    let mut i = 0;
+    while i < input.len() {
+        if input[i] != b'=' {
+            bytes.push(input[i]);
+            i += 1;
+            continue;
+        }
+        let rest = &input[i + 1..];
+        if rest.starts_with(b"\r\n") {
+            i += 3;
+            evidence = true;
+        } else if rest.starts_with(b"\n") {
+            i += 2;
+            evidence = true;
+        } else {
+            let high = char::from(*rest.first()?).to_digit(16)?;
+            let low = char::from(*rest.get(1)?).to_digit(16)?;
+            let byte = (high * 16 + low) as u8;
+            evidence |= matches!(byte, b'=' | b' ');
+            bytes.push(byte);
+            i += 3;
+        }
+    }
+    evidence.then_some(bytes)
+}
+
+/// A message body transcoded from the charset it declares.
+///
+/// Returned by [`EmailDetails::decoded_body`](crate::EmailDetails::decoded_body) and
+/// [`decode_body`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct DecodedBody<'a> {
+    /// The body as readable UTF-8 text.
+    pub text: Cow<'a, str>,
+    /// Name of the charset the body was transcoded from, or `None` if it was left as is.
+    pub charset: Option<&'static str>,
+    /// Whether some bytes were invalid in that charset and replaced with `U+FFFD`.
+    pub lossy: bool,
+}
+
+/// Repair a body whose legacy-charset bytes were passed through as Latin-1 characters.
+///
+/// GuerrillaMail hands over message bytes in a declared charset (`<meta charset="gbk">`,
+/// `charset=iso-8859-1`, or UTF-8 read the wrong way) as if every byte were one Latin-1 or
+/// Windows-1252 character, which shows up as mojibake such as `GrÃ¼ÃŸe`. When `body` declares
+/// a charset and looks like such a byte-for-character string, the bytes are recovered and
+/// decoded in the declared charset. Bodies without a declaration, bodies that already contain
+/// other characters, and Latin-1 or Windows-1252 bodies are returned unchanged.
+///
+/// # Example
+/// ```
+/// use guerrillamail_client::mime::decode_body;
+///
+/// let body = decode_body("<meta charset=\"utf-8\"><p>GrÃ¼ÃŸe</p>");
+/// assert_eq!(body.text, "<meta charset=\"utf-8\"><p>Grüße</p>");
+/// assert_eq!(body.charset, Some("UTF-8"));
+/// assert!(!body.lossy);
+/// ```
+pub fn decode_body(body: &str) -> DecodedBody<'_> {
+    let unchanged = DecodedBody {
+        text: Cow::Borrowed(body),
+        charset: None,
+        lossy: false,
+    };
+    let Some(encoding) =
+        declared_charset(body).and_then(|label| encoding_rs::Encoding::for_label(label.as_bytes()))
+    else {
+        return unchanged;
+    };
+    if encoding == encoding_rs::WINDOWS_1252 || body.is_ascii() {
+        return unchanged;
+    }
+    let Some(bytes) = body.chars().map(single_byte).collect::<Option<Vec<u8>>>() else {
+        return unchanged;
+    };
+
+    let (text, lossy) = encoding.decode_without_bom_handling(&bytes);
+    DecodedBody {
+        text: Cow::Owned(text.into_owned()),
+        charset: Some(encoding.name()),
+        lossy,
+    }
+}
+
+/// The byte `c` stands for when a body was read as Latin-1 or Windows-1252.
+fn single_byte(c: char) -> Option<u8> {
+    if let Ok(byte) = u8::try_from(u32::from(c)) {
+        return Some(byte);
+    }
+    let mut buf = [0; 4];
+    let (bytes, _, unmappable) = encoding_rs::WINDOWS_1252.encode(c.encode_utf8(&mut buf));
+    (!unmappable && bytes.len() == 1).then(|| bytes[0])
+}
+
+/// The charset named by the first `charset=` declaration in `body`, lowercased.
+fn declared_charset(body: &str) -> Option<String> {
+    let lower = body.to_ascii_lowercase();
+    let start = lower.find("charset")? + "charset".len();
+    let value = lower[start..].trim_start().strip_prefix('=')?.trim_start();
+    let value = value.trim_start_matches(['"', '\'']);
+    let label: String = value
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ':' | '.'))
+        .collect();
+    (!label.is_empty()).then_some(label)
+}
+
+/// Decode the encoded-word at the start of `input`, returning its text and length.
+fn decode_word(input: &str) -> Option<(String, usize)> {
+    let body = input.strip_prefix("=?")?;
+    let (charset, body) = body.split_once('?')?;
+    let (encoding, body) = body.split_once('?')?;
+    let end = body.find("?=")?;
+    let text = &body[..end];
+    if charset.is_empty() || charset.contains(char::is_whitespace) || text.contains(' ') {
+        return None;
+    }
+
+    let bytes = match encoding {
+        "B" | "b" => BASE64.decode(text).ok()?,
+        "Q" | "q" => decode_q(text)?,
+        _ => return None,
+    };
+    // RFC 2231 allows a language suffix: `UTF-8*en`.
+    let charset = charset.split('*').next().unwrap_or(charset);
+    let len = input.len() - body.len() + end + 2;
+    Some((decode_charset(&bytes, charset)?, len))
+}
+
+/// Decode the `Q` encoding: `_` is a space and `=XX` a hex-escaped byte.
+fn decode_q(text: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut input = text.bytes();
+    while let Some(byte) = input.next() {
+        match byte {
+            b'_' => bytes.push(b' '),
+            b'=' => {
+                let high = (input.next()? as char).to_digit(16)?;
+                let low = (input.next()? as char).to_digit(16)?;
+                bytes.push((high * 16 + low) as u8);
+            }
+            other => bytes.push(other),
+        }
+    }
+    Some(bytes)
+}
+
+/// Decode `bytes` in the named charset, or `None` if the charset is unknown.
+fn decode_charset(bytes: &[u8], charset: &str) -> Option<String> {
+    let encoding = encoding_rs::Encoding::for_label(charset.trim().as_bytes())?;
+    let (text, _) = encoding.decode_without_bom_handling(bytes);
+    Some(text.into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_encoded_words_and_leaves_everything_else() {
+        assert_eq!(
+            decode_header("\"=?utf-8?q?J=C3=BCrgen_M=C3=BCller?=\" <jm@example.com>"),
+            "\"Jürgen Müller\" <jm@example.com>"
+        );
+        assert_eq!(decode_header("=?GBK?B?xOO6ww==?= world"), "你好 world");
+        assert_eq!(decode_header("=?UTF-8?B?QQ?=  =?UTF-8?B?Qg==?= C"), "AB C");
+        assert_eq!(decode_header("=?unknown?B?QQ==?="), "=?unknown?B?QQ==?=");
+        assert_eq!(decode_header("50% off =?=?"), "50% off =?=?");
+    }
+
+    #[test]
+    fn undoes_transfer_encodings_but_leaves_plain_bodies() {
+        assert_eq!(decode_transfer_encoding("a=3Db=\r\nc"), "a=bc");
+        assert_eq!(
+            decode_transfer_encoding("PHA+SGVsbG8sIHdv\ncmxkITwvcD4K\n"),
+            "<p>Hello, world!</p>\n"
+        );
+        // Latin-1 bytes survive for `decode_body`.
+        assert_eq!(
+            decode_transfer_encoding("caf=E9=20au lait"),
+            "caf\u{e9} au lait"
+        );
+
+        for plain in [
+            "<a href=\"https://example.com\">link</a>",
+            "Your code is 123456",
+            "SGVsbG8",
+            "1+1=2",
+            "AAAAAAAAAAAAAAAAAAAA",
+            "https://x.com/verify?token=deadbeef",
+            "abcdefghijklmnopqrstuvwx",
+            "caf=E9",
+        ] {
+            assert_eq!(decode_transfer_encoding(plain), plain);
+        }
+    }
+
+    #[test]
+    fn transcodes_bodies_from_their_declared_charset() {
+        let latin1 = |bytes: &[u8]| bytes.iter().map(|&b| char::from(b)).collect::<String>();
+
+        let gbk = latin1(b"<meta content=\"text/html; charset=GBK\">\xc4\xe3\xba\xc3");
+        let body = decode_body(&gbk);
+        assert!(body.text.ends_with("你好"));
+        assert_eq!((body.charset, body.lossy), (Some("GBK"), false));
+
+        let broken = latin1(b"<meta charset='utf-8'>caf\xc3");
+        let body = decode_body(&broken);
+        assert!(body.text.ends_with("caf\u{fffd}"));
+        assert!(body.lossy);
+
+        let unchanged = [
+            "<meta charset=utf-8><p>Grüße 你好</p>",
+            "<p>Grüße</p>",
+            "charset=latin1 é",
+        ];
+        for unchanged in unchanged {
+            let body = decode_body(unchanged);
+            assert_eq!((body.text.as_ref(), body.charset), (unchanged, None));
+        }
+    }
+}
@@ -1,26 +1,61 @@
 //! Wire models returned by GuerrillaMail API calls used by [`Client`](crate::Client).
 
-use serde::Deserialize;
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, NaiveDateTime, Utc};
 use serde::Deserializer;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
 use std::fmt;
 
 /// An email message header returned by GuerrillaMail.
-#[derive(Debug, Clone, Deserialize)]
+///
+/// Serializes back to GuerrillaMail's field names, so messages can be saved as fixtures.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[non_exhaustive]
 pub struct Message {
     /// Unique message ID.
     pub mail_id: String,
     /// Sender email address.
+    #[cfg_attr(feature = "mime", serde(deserialize_with = "de_header"))]
     pub mail_from: String,
     /// Email subject line.
+    #[cfg_attr(feature = "mime", serde(deserialize_with = "de_header"))]
     pub mail_subject: String,
     /// Short excerpt of the email body.
     pub mail_excerpt: String,
     /// Unix timestamp in seconds (string) of when the email was received.
     pub mail_timestamp: String,
+    /// Receive time as formatted by GuerrillaMail (`YYYY-MM-DD HH:MM:SS`), if sent.
+    #[serde(default)]
+    pub mail_date: String,
+    /// Whether GuerrillaMail reports the message as read (`mail_read`, sent as `0`/`1`).
+    #[serde(default, deserialize_with = "de_bool_str_or_num")]
+    pub mail_read: bool,
+    /// Message size in bytes (if provided by API).
+    #[serde(default, deserialize_with = "de_u64_str_or_num_opt")]
+    pub mail_size: Option<u64>,
+    /// Attachment count (if provided by API).
+    #[serde(default, rename = "att", deserialize_with = "de_u32_str_or_num_opt")]
+    pub attachment_count: Option<u32>,
+    /// Fields this crate does not model yet, kept as sent by GuerrillaMail.
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, Value>,
+}
+
+#[cfg(feature = "chrono")]
+impl Message {
+    /// When the email was received, from `mail_timestamp` (or `mail_date` as a fallback).
+    ///
+    /// Returns `None` when neither field holds a valid time.
+    pub fn received_at(&self) -> Option<DateTime<Utc>> {
+        parse_received(&self.mail_timestamp, &self.mail_date)
+    }
 }
 
 /// Attachment metadata returned by GuerrillaMail.
-#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[non_exhaustive]
 pub struct Attachment {
     /// Original filename.
     #[serde(default, rename = "f")]
@@ -31,6 +66,9 @@ pub struct Attachment {
     /// Attachment part ID used for download.
     #[serde(default, rename = "p")]
     pub part_id: String,
+    /// Fields this crate does not model yet, kept as sent by GuerrillaMail.
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, Value>,
 }
 
 #[derive(Deserialize)]
@@ -58,19 +96,158 @@ where
     }
 }
 
-/// Full email details including body content.
+fn de_u64_str_or_num_opt<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<StrOrNumU32>::deserialize(deserializer)?
+        .map(|value| match value {
+            StrOrNumU32::Str(raw) => raw.trim().parse::<u64>().map_err(serde::de::Error::custom),
+            StrOrNumU32::Num(num) => Ok(num),
+        })
+        .transpose()
+}
+
+fn de_u64_str_or_num<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match StrOrNumU32::deserialize(deserializer)? {
+        StrOrNumU32::Str(raw) => raw.trim().parse::<u64>().map_err(serde::de::Error::custom),
+        StrOrNumU32::Num(num) => Ok(num),
+    }
+}
+
+/// Deserialize a header value, decoding RFC 2047 encoded-words.
+#[cfg(feature = "mime")]
+fn de_header<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let raw = String::deserialize(deserializer)?;
+    Ok(match crate::mime::decode_header(&raw) {
+        std::borrow::Cow::Borrowed(_) => raw,
+        std::borrow::Cow::Owned(decoded) => decoded,
+    })
+}
+
+fn de_bool_str_or_num<'de, D>(deserializer: D) -> Result<bool, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Flag {
+        Bool(bool),
+        Num(u64),
+        Str(String),
+    }
+
+    match Option::<Flag>::deserialize(deserializer)? {
+        None => Ok(false),
+        Some(Flag::Bool(b)) => Ok(b),
+        Some(Flag::Num(n)) => Ok(n != 0),
+        Some(Flag::Str(raw)) => match raw.trim() {
+            "" | "0" | "false" => Ok(false),
+            "1" | "true" => Ok(true),
+            other => Err(serde::de::Error::custom(format!("invalid flag `{other}`"))),
+        },
+    }
+}
+
+/// The address currently assigned to a GuerrillaMail session.
+///
+/// Returned by [`Client::current_address`](crate::Client::current_address).
 #[derive(Clone, Deserialize)]
+pub struct CurrentAddress {
+    /// Full email address (`alias@domain`).
+    pub email_addr: String,
+    /// Unix timestamp in seconds of when the address was set or last extended.
+    #[serde(deserialize_with = "de_u64_str_or_num")]
+    pub email_timestamp: u64,
+    /// Local-part of the address as reported by GuerrillaMail.
+    #[serde(default)]
+    pub alias: String,
+    /// Session token sometimes returned by the API.
+    #[serde(default)]
+    pub sid_token: Option<String>,
+}
+
+impl fmt::Debug for CurrentAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CurrentAddress")
+            .field("email_addr", &self.email_addr)
+            .field("email_timestamp", &self.email_timestamp)
+            .field("alias", &self.alias)
+            .field("sid_token", &self.sid_token.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
+}
+
+/// An inbox listing together with the metadata GuerrillaMail sends alongside it.
+///
+/// Returned by [`Client::get_inbox`](crate::Client::get_inbox). Metadata fields are `None`
+/// when the response omits them.
+#[derive(Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct InboxSnapshot {
+    /// Messages currently in the inbox.
+    pub messages: Vec<Message>,
+    /// Total number of messages GuerrillaMail reports for the inbox (`count`).
+    pub count: Option<u64>,
+    /// Full address the listing belongs to (`email`).
+    pub email: Option<String>,
+    /// Local-part of the address (`alias`).
+    pub alias: Option<String>,
+    /// Server time of the listing as a Unix timestamp in seconds (`ts`).
+    pub timestamp: Option<u64>,
+    /// Session token sometimes returned by the API.
+    pub sid_token: Option<String>,
+    /// Number of GuerrillaMail users currently online (`users`).
+    pub users: Option<u64>,
+}
+
+impl fmt::Debug for InboxSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InboxSnapshot")
+            .field("messages", &self.messages)
+            .field("count", &self.count)
+            .field("email", &self.email)
+            .field("alias", &self.alias)
+            .field("timestamp", &self.timestamp)
+            .field("sid_token", &self.sid_token.as_ref().map(|_| "<redacted>"))
+            .field("users", &self.users)
+            .finish()
+    }
+}
+
+/// Full email details including body content.
+///
+/// Serializes back to GuerrillaMail's field names, except for `sid_token`, which is never written.
+#[derive(Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[non_exhaustive]
 pub struct EmailDetails {
     /// Unique message ID.
     pub mail_id: String,
     /// Sender email address.
+    #[cfg_attr(feature = "mime", serde(deserialize_with = "de_header"))]
     pub mail_from: String,
     /// Email subject line.
+    #[cfg_attr(feature = "mime", serde(deserialize_with = "de_header"))]
     pub mail_subject: String,
     /// Full HTML body of the email.
     pub mail_body: String,
     /// Unix timestamp in seconds (string) of when the email was received.
     pub mail_timestamp: String,
+    /// Receive time as formatted by GuerrillaMail (`YYYY-MM-DD HH:MM:SS`), if sent.
+    #[serde(default)]
+    pub mail_date: String,
+    /// Whether GuerrillaMail reports the message as read (`mail_read`, sent as `0`/`1`).
+    #[serde(default, deserialize_with = "de_bool_str_or_num")]
+    pub mail_read: bool,
+    /// Message size in bytes (if provided by API).
+    #[serde(default, deserialize_with = "de_u64_str_or_num_opt")]
+    pub mail_size: Option<u64>,
     /// Attachment metadata entries (if any); see [`Attachment`].
     #[serde(default, rename = "att_info")]
     pub attachments: Vec<Attachment>,
@@ -78,8 +255,11 @@ pub struct EmailDetails {
     #[serde(default, rename = "att", deserialize_with = "de_u32_str_or_num_opt")]
     pub attachment_count: Option<u32>,
     /// Session token sometimes returned by the API.
-    #[serde(default)]
+    #[serde(default, skip_serializing)]
     pub sid_token: Option<String>,
+    /// Fields this crate does not model yet, kept as sent by GuerrillaMail.
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, Value>,
 }
 
 impl fmt::Debug for EmailDetails {
@@ -90,13 +270,154 @@ impl fmt::Debug for EmailDetails {
             .field("mail_subject", &self.mail_subject)
             .field("mail_body", &self.mail_body)
             .field("mail_timestamp", &self.mail_timestamp)
+            .field("mail_date", &self.mail_date)
+            .field("mail_read", &self.mail_read)
+            .field("mail_size", &self.mail_size)
             .field("attachments", &self.attachments)
             .field("attachment_count", &self.attachment_count)
             .field("sid_token", &self.sid_token.as_ref().map(|_| "<redacted>"))
+            .field("extra", &self.extra)
             .finish()
     }
 }
 
+impl EmailDetails {
+    /// The body as plain text, for assertions and code extraction.
+    ///
+    /// Strips tags (block elements become line breaks), skips `head`, `script`, and `style`,
+    /// resolves character references such as `&amp;` and `&#39;`, and normalizes whitespace.
+    /// With the `mime` feature the body is [decoded](Self::decoded_body) first.
+    ///
+    /// # Example
+    /// ```
+    /// # use guerrillamail_client::EmailDetails;
+    /// # let details: EmailDetails = serde_json::from_value(serde_json::json!({
+    /// #     "mail_id": "1", "mail_from": "a@example.com", "mail_subject": "Code",
+    /// #     "mail_timestamp": "1700000000",
+    /// #     "mail_body": "<p>Your code is <b>493817</b></p><p>Thanks &amp; bye</p>",
+    /// # })).unwrap();
+    /// assert_eq!(details.body_text(), "Your code is 493817\n\nThanks & bye");
+    /// ```
+    pub fn body_text(&self) -> String {
+        crate::html::to_text(&self.readable_body())
+    }
+
+    /// The HTML body with scripts, event handlers, and remote trackers removed.
+    ///
+    /// Safe to embed in web dashboards or render in a terminal UI: `script`, `iframe`, `object`,
+    /// `svg`, and similar elements are dropped with their content, `on*` attributes and URLs
+    /// outside `http`, `https`, `mailto`, `cid`, and inline images are stripped, and remotely
+    /// loaded images (tracking pixels) are removed. Links and formatting are kept. With the
+    /// `mime` feature the body is [decoded](Self::decoded_body) first.
+    ///
+    /// # Example
+    /// ```
+    /// # use guerrillamail_client::EmailDetails;
+    /// # let details: EmailDetails = serde_json::from_value(serde_json::json!({
+    /// #     "mail_id": "1", "mail_from": "a@example.com", "mail_subject": "Hi",
+    /// #     "mail_timestamp": "1700000000",
+    /// #     "mail_body": "<p onclick=\"x()\">Hi<script>x()</script></p>",
+    /// # })).unwrap();
+    /// assert_eq!(details.body_sanitized(), "<p>Hi</p>");
+    /// ```
+    pub fn body_sanitized(&self) -> String {
+        crate::html::sanitize(&self.readable_body())
+    }
+
+    /// Every link in the body, in order: `<a href>` targets with their anchor text, and bare
+    /// `http(s)://` URLs written out in the text. With the `mime` feature the body is
+    /// [decoded](Self::decoded_body) first, so quoted-printable `href=3D"…"` attributes resolve.
+    ///
+    /// # Example
+    /// ```
+    /// # use guerrillamail_client::EmailDetails;
+    /// # let details: EmailDetails = serde_json::from_value(serde_json::json!({
+    /// #     "mail_id": "1", "mail_from": "a@example.com", "mail_subject": "Verify",
+    /// #     "mail_timestamp": "1700000000",
+    /// #     "mail_body": "<a href=\"https://example.com/verify?t=1\">Confirm</a>",
+    /// # })).unwrap();
+    /// let link = &details.links()[0];
+    /// assert_eq!(link.url, "https://example.com/verify?t=1");
+    /// assert_eq!(link.text.as_deref(), Some("Confirm"));
+    /// ```
+    pub fn links(&self) -> Vec<crate::ExtractedLink> {
+        crate::html::links(&self.readable_body())
+    }
+
+    /// Pull a one-time code out of the subject and body text.
+    ///
+    /// The subject is searched first, then [`body_text`](Self::body_text), as one text; see
+    /// [`CodePattern`](crate::CodePattern) for the built-in patterns.
+    ///
+    /// # Example
+    /// ```
+    /// # use guerrillamail_client::{CodePattern, EmailDetails};
+    /// # let details: EmailDetails = serde_json::from_value(serde_json::json!({
+    /// #     "mail_id": "1", "mail_from": "a@example.com", "mail_subject": "Order 2024",
+    /// #     "mail_timestamp": "1700000000",
+    /// #     "mail_body": "<p>Your verification code is <b>493817</b>.</p>",
+    /// # })).unwrap();
+    /// assert_eq!(details.extract_code(CodePattern::Digits).as_deref(), Some("493817"));
+    /// ```
+    pub fn extract_code(&self, pattern: crate::CodePattern) -> Option<String> {
+        pattern.find(&format!("{}\n{}", self.mail_subject, self.body_text()))
+    }
+
+    /// The body the text, link, and sanitizing helpers work on.
+    fn readable_body(&self) -> std::borrow::Cow<'_, str> {
+        #[cfg(feature = "mime")]
+        return self.decoded_body().text;
+        #[cfg(not(feature = "mime"))]
+        std::borrow::Cow::Borrowed(&self.mail_body)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl EmailDetails {
+    /// When the email was received, from `mail_timestamp` (or `mail_date` as a fallback).
+    ///
+    /// Returns `None` when neither field holds a valid time.
+    pub fn received_at(&self) -> Option<DateTime<Utc>> {
+        parse_received(&self.mail_timestamp, &self.mail_date)
+    }
+}
+
+#[cfg(feature = "mime")]
+impl EmailDetails {
+    /// [`mail_body`](Self::mail_body) with any leftover transfer encoding undone, transcoded
+    /// from the charset it declares.
+    ///
+    /// See [`mime::decode_transfer_encoding`](crate::mime::decode_transfer_encoding) and
+    /// [`mime::decode_body`](crate::mime::decode_body) for when the body is changed.
+    pub fn decoded_body(&self) -> crate::mime::DecodedBody<'_> {
+        match crate::mime::decode_transfer_encoding(&self.mail_body) {
+            std::borrow::Cow::Borrowed(body) => crate::mime::decode_body(body),
+            std::borrow::Cow::Owned(body) => {
+                let decoded = crate::mime::decode_body(&body);
+                crate::mime::DecodedBody {
+                    text: std::borrow::Cow::Owned(decoded.text.into_owned()),
+                    ..decoded
+                }
+            }
+        }
+    }
+}
+
+/// Parse a Unix `timestamp`, falling back to a `YYYY-MM-DD HH:MM:SS` UTC `date`.
+#[cfg(feature = "chrono")]
+fn parse_received(timestamp: &str, date: &str) -> Option<DateTime<Utc>> {
+    timestamp
+        .trim()
+        .parse::<i64>()
+        .ok()
+        .and_then(|secs| DateTime::from_timestamp(secs, 0))
+        .or_else(|| {
+            NaiveDateTime::parse_from_str(date.trim(), "%Y-%m-%d %H:%M:%S")
+                .ok()
+                .map(|naive| naive.and_utc())
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -142,6 +463,7 @@ mod tests {
                 filename: "file.txt".to_string(),
                 content_type_or_hint: Some("text/plain".to_string()),
                 part_id: "99".to_string(),
+                extra: BTreeMap::new(),
             }]
         );
         assert_eq!(details.sid_token.as_deref(), Some("sid123"));
@@ -175,4 +497,208 @@ mod tests {
         let details: EmailDetails = serde_json::from_value(value).unwrap();
         assert!(details.attachment_count.is_none());
     }
+
+    #[test]
+    fn current_address_deserialize_numeric_timestamp() {
+        let value = json!({
+            "email_addr": "alias@example.com",
+            "email_timestamp": 1700000000,
+            "alias": "alias",
+            "sid_token": "sid123"
+        });
+
+        let current: CurrentAddress = serde_json::from_value(value).unwrap();
+        assert_eq!(current.email_addr, "alias@example.com");
+        assert_eq!(current.email_timestamp, 1_700_000_000);
+        assert_eq!(current.alias, "alias");
+        assert_eq!(current.sid_token.as_deref(), Some("sid123"));
+    }
+
+    #[test]
+    fn message_deserialize_mail_read_flag() {
+        let base = json!({
+            "mail_id": "1",
+            "mail_from": "sender@example.com",
+            "mail_subject": "Hello",
+            "mail_excerpt": "Hi",
+            "mail_timestamp": "1700000000"
+        });
+
+        let unread: Message = serde_json::from_value(base.clone()).unwrap();
+        assert!(!unread.mail_read);
+
+        let mut value = base;
+        value["mail_read"] = json!("1");
+        let read: Message = serde_json::from_value(value).unwrap();
+        assert!(read.mail_read);
+    }
+
+    #[test]
+    fn message_deserialize_string_encoded_numbers() {
+        let value = json!({
+            "mail_id": "1",
+            "mail_from": "sender@example.com",
+            "mail_subject": "Hello",
+            "mail_excerpt": "Hi",
+            "mail_timestamp": "1700000000",
+            "mail_read": 1,
+            "mail_size": "2048",
+            "att": "2"
+        });
+
+        let message: Message = serde_json::from_value(value).unwrap();
+        assert!(message.mail_read);
+        assert_eq!(message.mail_size, Some(2048));
+        assert_eq!(message.attachment_count, Some(2));
+        assert!(message.extra.is_empty());
+
+        let invalid = json!({
+            "mail_id": "1",
+            "mail_from": "sender@example.com",
+            "mail_subject": "Hello",
+            "mail_excerpt": "Hi",
+            "mail_timestamp": "1700000000",
+            "mail_size": "large"
+        });
+        assert!(serde_json::from_value::<Message>(invalid).is_err());
+    }
+
+    #[test]
+    fn unknown_fields_are_kept_in_extra() {
+        let value = json!({
+            "mail_id": "123",
+            "mail_from": "sender@example.com",
+            "mail_subject": "Hello",
+            "mail_body": "<p>Body</p>",
+            "mail_timestamp": "1700000000",
+            "att_info": [{ "f": "file.txt", "p": "99", "s": 12 }],
+            "sid_token": "sid123",
+            "content_type": "text/html",
+            "mail_recipient": "alias"
+        });
+
+        let details: EmailDetails = serde_json::from_value(value).unwrap();
+        assert_eq!(details.extra.len(), 2);
+        assert_eq!(details.extra["content_type"], "text/html");
+        assert_eq!(details.extra["mail_recipient"], "alias");
+        assert_eq!(details.attachments[0].extra["s"], 12);
+        assert_eq!(details.sid_token.as_deref(), Some("sid123"));
+    }
+
+    #[test]
+    fn models_round_trip_through_serialize() {
+        let value = json!({
+            "mail_id": "123",
+            "mail_from": "sender@example.com",
+            "mail_subject": "Hello",
+            "mail_body": "<p>Body</p>",
+            "mail_timestamp": "1700000000",
+            "mail_size": "2048",
+            "att": "1",
+            "att_info": [{ "f": "file.txt", "t": "text/plain", "p": "99" }],
+            "sid_token": "sid123",
+            "content_type": "text/html"
+        });
+        let details: EmailDetails = serde_json::from_value(value).unwrap();
+
+        let written = serde_json::to_value(&details).unwrap();
+        assert!(written.get("sid_token").is_none());
+        assert_eq!(written["att_info"][0]["f"], "file.txt");
+        assert_eq!(written["content_type"], "text/html");
+
+        let reread: EmailDetails = serde_json::from_value(written).unwrap();
+        assert_eq!(
+            reread,
+            EmailDetails {
+                sid_token: None,
+                ..details.clone()
+            }
+        );
+        let set: std::collections::HashSet<_> = [reread.attachments[0].clone()].into();
+        assert!(set.contains(&details.attachments[0]));
+    }
+
+    #[cfg(feature = "mime")]
+    #[test]
+    fn headers_are_decoded_during_deserialization() {
+        let value = json!({
+            "mail_id": "1",
+            "mail_from": "=?UTF-8?Q?Zo=C3=AB?= <zoe@example.com>",
+            "mail_subject": "=?UTF-8?B?R3LDvMOfZQ==?=",
+            "mail_excerpt": "",
+            "mail_timestamp": "1700000000"
+        });
+
+        let message: Message = serde_json::from_value(value).unwrap();
+        assert_eq!(message.mail_from, "Zoë <zoe@example.com>");
+        assert_eq!(message.mail_subject, "Grüße");
+    }
+
+    #[cfg(feature = "mime")]
+    #[test]
+    fn bodies_are_kept_as_received_and_decoded_on_request() {
+        let details = |body: &str| -> EmailDetails {
+            serde_json::from_value(json!({
+                "mail_id": "1",
+                "mail_from": "a@example.com",
+                "mail_subject": "Hi",
+                "mail_body": body,
+                "mail_timestamp": "1700000000"
+            }))
+            .unwrap()
+        };
+
+        let encoded = details("<p class=3D\"x\">Gr=C3=BC=C3=9Fe</p>");
+        assert_eq!(encoded.mail_body, "<p class=3D\"x\">Gr=C3=BC=C3=9Fe</p>");
+        assert_eq!(encoded.decoded_body().text, "<p class=\"x\">Grüße</p>");
+
+        let link = details("https://x.com/verify?token=deadbeef");
+        assert_eq!(link.decoded_body().text, link.mail_body);
+        assert_eq!(link.links()[0].url, "https://x.com/verify?token=deadbeef");
+    }
+
+    #[cfg(feature = "mime")]
+    #[test]
+    fn links_and_sanitized_body_read_quoted_printable_hrefs() {
+        let details: EmailDetails = serde_json::from_value(json!({
+            "mail_id": "1",
+            "mail_from": "a@example.com",
+            "mail_subject": "Verify",
+            "mail_body": "<a href=3D\"https://example.com/verify?t=3D1\">Confirm</a>",
+            "mail_timestamp": "1700000000"
+        }))
+        .unwrap();
+
+        let links = details.links();
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].url, "https://example.com/verify?t=1");
+        assert_eq!(links[0].text.as_deref(), Some("Confirm"));
+        assert_eq!(
+            details.body_sanitized(),
+            "<a href=\"https://example.com/verify?t=1\">Confirm</a>"
+        );
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn message_received_at_parses_timestamp_or_date() {
+        let value = json!({
+            "mail_id": "1",
+            "mail_from": "sender@example.com",
+            "mail_subject": "Hello",
+            "mail_excerpt": "Hi",
+            "mail_timestamp": "1700000000",
+            "mail_date": "2023-11-14 22:13:20"
+        });
+
+        let mut message: Message = serde_json::from_value(value).unwrap();
+        let expected = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        assert_eq!(message.received_at(), Some(expected));
+
+        message.mail_timestamp.clear();
+        assert_eq!(message.received_at(), Some(expected));
+
+        message.mail_date = "12:00:00".to_string();
+        assert_eq!(message.received_at(), None);
+    }
 }
@@ -0,0 +1,125 @@
+//! One-time code extraction from message bodies.
+
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Standalone runs of 4–8 digits, the shape of almost every emailed one-time code.
+static DIGITS: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\b\d{4,8}\b").unwrap());
+
+/// Standalone runs of 6–12 letters and digits; only those mixing both count as tokens.
+static TOKENS: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\b[A-Za-z0-9]{6,12}\b").unwrap());
+
+/// A code introduced by "code is", "OTP:", "PIN is:", and similar phrasing.
+static PHRASE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(?:code|otp|pin|passcode)(?:\s+is\b\s*:?|\s*:)\s*([A-Za-z0-9-]{4,12})\b")
+        .unwrap()
+});
+
+/// Words that usually sit right before a code.
+const CUES: [&str; 5] = ["code", "otp", "pin", "passcode", "verification"];
+
+/// What [`EmailDetails::extract_code`](crate::EmailDetails::extract_code) looks for.
+///
+/// The built-in patterns prefer a match that follows a cue word such as "code" or "OTP" within
+/// the preceding 40 bytes, falling back to the first match anywhere.
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub enum CodePattern {
+    /// A number of 4–8 digits, such as `493817`.
+    #[default]
+    Digits,
+    /// A 6–12 character token mixing letters and digits, such as `AB12CD`.
+    Alphanumeric,
+    /// Whatever follows "code is", "code:", "OTP:", or "PIN is", such as `X7-K2P9`.
+    Phrase,
+    /// The first match of a custom regex, or its first capture group if it has one.
+    Regex(Regex),
+}
+
+impl CodePattern {
+    /// The code this pattern finds in `text`, if any.
+    pub fn find(&self, text: &str) -> Option<String> {
+        let found = match self {
+            CodePattern::Digits => cued(text, DIGITS.find_iter(text)),
+            CodePattern::Alphanumeric => {
+                let tokens = TOKENS.find_iter(text).filter(|found| {
+                    let token = found.as_str();
+                    token.bytes().any(|b| b.is_ascii_digit())
+                        && token.bytes().any(|b| b.is_ascii_alphabetic())
+                });
+                cued(text, tokens)
+            }
+            CodePattern::Phrase => PHRASE.captures(text)?.get(1),
+            CodePattern::Regex(regex) => {
+                let captures = regex.captures(text)?;
+                captures.get(1).or_else(|| captures.get(0))
+            }
+        };
+        found.map(|found| found.as_str().to_string())
+    }
+}
+
+/// The first of `candidates` preceded by a cue word, or else the first candidate.
+fn cued<'t>(
+    text: &str,
+    candidates: impl Iterator<Item = regex::Match<'t>>,
+) -> Option<regex::Match<'t>> {
+    let lower = text.to_ascii_lowercase();
+    let mut first = None;
+    for found in candidates {
+        let before = &lower[..found.start()];
+        let mut start = before.len().saturating_sub(40);
+        while !before.is_char_boundary(start) {
+            start += 1;
+        }
+        let window = &before[start..];
+        if CUES.iter().any(|cue| window.contains(cue)) {
+            return Some(found);
+        }
+        first.get_or_insert(found);
+    }
+    first
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn digits_prefer_numbers_after_a_cue_word() {
+        let text = "Order 2024 shipped. Your verification code is 493817.";
+        assert_eq!(CodePattern::Digits.find(text).as_deref(), Some("493817"));
+        assert_eq!(
+            CodePattern::Digits.find("Use 1234 to log in").as_deref(),
+            Some("1234")
+        );
+        assert_eq!(CodePattern::Digits.find("No digits here"), None);
+    }
+
+    #[test]
+    fn alphanumeric_tokens_mix_letters_and_digits() {
+        let text = "Welcome aboard 2024! Your code: AB12CD";
+        assert_eq!(
+            CodePattern::Alphanumeric.find(text).as_deref(),
+            Some("AB12CD")
+        );
+        assert_eq!(
+            CodePattern::Alphanumeric.find("Welcome aboard 123456"),
+            None
+        );
+    }
+
+    #[test]
+    fn phrase_and_custom_patterns() {
+        let text = "Hi 2024 team, your one-time code is: X7-K2P9. Thanks";
+        assert_eq!(CodePattern::Phrase.find(text).as_deref(), Some("X7-K2P9"));
+        assert_eq!(
+            CodePattern::Phrase.find("OTP:884213").as_deref(),
+            Some("884213")
+        );
+        assert_eq!(CodePattern::Phrase.find("Use 1234 to log in"), None);
+
+        let pattern = CodePattern::Regex(Regex::new(r"token: ([A-Z0-9]{6})").unwrap());
+        assert_eq!(pattern.find("token: AB12CD").as_deref(), Some("AB12CD"));
+    }
+}
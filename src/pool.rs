@@ -0,0 +1,468 @@
+//! Pre-warmed pool of inboxes for amortizing address creation.
+
+use crate::{AliasGenerator, CancellationToken, Client, Error, INBOX_LIFETIME, Inbox, Result};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
+use tokio::sync::Semaphore;
+
+/// A pool of pre-created inboxes handed out via [`acquire`](InboxPool::acquire) and
+/// [`release`](InboxPool::release).
+///
+/// All addresses are created up front by [`InboxPoolBuilder::build`], so acquiring one does not
+/// wait on GuerrillaMail. A background task periodically replaces idle inboxes that are about to
+/// expire; an inbox that is already stale when acquired is replaced inline before being returned.
+///
+/// `Clone` is cheap; clones share the same pool. The background task stops once every clone has
+/// been dropped. Inboxes are not forgotten server-side when the pool goes away.
+///
+/// # Example
+/// ```no_run
+/// # use guerrillamail_client::{Client, InboxPool};
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), guerrillamail_client::Error> {
+/// let client = Client::new().await?;
+/// let pool = InboxPool::builder(client).size(4).build().await?;
+///
+/// let inbox = pool.acquire().await?;
+/// println!("Using {}", inbox.address());
+/// pool.release(inbox);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct InboxPool {
+    inner: Arc<PoolInner>,
+}
+
+#[derive(Debug)]
+struct PoolInner {
+    client: Client,
+    generator: AliasGenerator,
+    refresh_margin: Duration,
+    idle: Mutex<VecDeque<Inbox>>,
+    /// Addresses handed out by `acquire` and not yet released.
+    lent: Mutex<Vec<String>>,
+    available: Semaphore,
+    cancel: CancellationToken,
+}
+
+impl InboxPool {
+    /// Create an [`InboxPoolBuilder`] that creates its inboxes through `client`.
+    pub fn builder(client: Client) -> InboxPoolBuilder {
+        InboxPoolBuilder::new(client)
+    }
+
+    /// Take an inbox out of the pool, waiting until one is released if all are in use.
+    ///
+    /// # Errors
+    /// - Returns any error from re-creating an inbox that had expired while idle.
+    /// - Returns `Error::Cancelled` if the pool's [`cancel_on`](InboxPoolBuilder::cancel_on)
+    ///   token is cancelled before an inbox becomes available.
+    ///
+    /// # Network
+    /// None, unless the idle inbox was stale and had to be replaced (one POST to `ajax.php`).
+    pub async fn acquire(&self) -> Result<Inbox> {
+        let permit = tokio::select! {
+            permit = self.inner.available.acquire() => {
+                permit.expect("inbox pool semaphore is never closed")
+            }
+            _ = self.inner.cancel.cancelled() => return Err(Error::Cancelled),
+        };
+
+        let inbox = self
+            .inner
+            .idle
+            .lock()
+            .expect("inbox pool poisoned")
+            .pop_front()
+            .expect("permit guarantees an idle inbox");
+
+        if !self.inner.is_stale(&inbox) {
+            permit.forget();
+            self.inner.lend(&inbox);
+            return Ok(inbox);
+        }
+
+        // If the replacement fails or this future is dropped mid-request, the stale inbox goes
+        // back to the pool, followed by its permit.
+        let mut stale = StaleInbox {
+            idle: &self.inner.idle,
+            inbox: Some(inbox),
+        };
+        let fresh = self.inner.create().await?;
+        stale.inbox = None;
+        permit.forget();
+        self.inner.lend(&fresh);
+        Ok(fresh)
+    }
+
+    /// Return an inbox to the pool so it can be handed out again.
+    ///
+    /// Only inboxes handed out by [`acquire`](InboxPool::acquire) are taken back, once each; any
+    /// other inbox, or one released twice, is dropped without growing the pool.
+    pub fn release(&self, inbox: Inbox) {
+        {
+            let mut lent = self.inner.lent.lock().expect("inbox pool poisoned");
+            let Some(index) = lent.iter().position(|address| address == inbox.address()) else {
+                return;
+            };
+            lent.swap_remove(index);
+        }
+        self.inner
+            .idle
+            .lock()
+            .expect("inbox pool poisoned")
+            .push_back(inbox);
+        self.inner.available.add_permits(1);
+    }
+
+    /// Number of inboxes currently idle in the pool.
+    pub fn idle(&self) -> usize {
+        self.inner.idle.lock().expect("inbox pool poisoned").len()
+    }
+}
+
+/// An idle inbox being replaced by [`InboxPool::acquire`], put back unless the replacement
+/// succeeds.
+struct StaleInbox<'a> {
+    idle: &'a Mutex<VecDeque<Inbox>>,
+    inbox: Option<Inbox>,
+}
+
+impl Drop for StaleInbox<'_> {
+    fn drop(&mut self) {
+        if let Some(inbox) = self.inbox.take() {
+            self.idle
+                .lock()
+                .expect("inbox pool poisoned")
+                .push_back(inbox);
+        }
+    }
+}
+
+impl PoolInner {
+    fn lend(&self, inbox: &Inbox) {
+        self.lent
+            .lock()
+            .expect("inbox pool poisoned")
+            .push(inbox.address().to_string());
+    }
+
+    async fn create(&self) -> Result<Inbox> {
+        self.client.create_inbox(&self.generator.generate()).await
+    }
+
+    fn is_stale(&self, inbox: &Inbox) -> bool {
+        inbox.expires_at() <= self.client.unix_now() + self.refresh_margin.as_secs()
+    }
+
+    /// Replace idle inboxes that are close to expiry.
+    async fn refresh_idle(&self) {
+        let stale: Vec<String> = self
+            .idle
+            .lock()
+            .expect("inbox pool poisoned")
+            .iter()
+            .filter(|inbox| self.is_stale(inbox))
+            .map(|inbox| inbox.address().to_string())
+            .collect();
+
+        for address in stale {
+            let Ok(fresh) = self.create().await else {
+                // Leave the stale inbox in place; `acquire` retries the replacement.
+                continue;
+            };
+            let mut idle = self.idle.lock().expect("inbox pool poisoned");
+            match idle.iter_mut().find(|inbox| inbox.address() == address) {
+                Some(slot) => *slot = fresh,
+                // Acquired in the meantime; the caller now owns the stale one.
+                None => drop(fresh),
+            }
+        }
+    }
+}
+
+/// Configures and fills an [`InboxPool`].
+///
+/// Defaults: 4 inboxes, [`AliasGenerator::default`] aliases, replacement of inboxes within
+/// 5 minutes of expiry, checked every 60 seconds.
+#[derive(Debug, Clone)]
+pub struct InboxPoolBuilder {
+    client: Client,
+    size: usize,
+    generator: AliasGenerator,
+    refresh_margin: Duration,
+    refresh_interval: Duration,
+    cancel: CancellationToken,
+}
+
+impl InboxPoolBuilder {
+    /// Create a builder with default settings.
+    pub fn new(client: Client) -> Self {
+        Self {
+            client,
+            size: 4,
+            generator: AliasGenerator::default(),
+            refresh_margin: Duration::from_secs(5 * 60),
+            refresh_interval: Duration::from_secs(60),
+            cancel: CancellationToken::new(),
+        }
+    }
+
+    /// Set the number of inboxes to create up front.
+    ///
+    /// # Panics
+    /// Panics if `size` is zero.
+    pub fn size(mut self, size: usize) -> Self {
+        assert!(size > 0, "inbox pool size must be non-zero");
+        self.size = size;
+        self
+    }
+
+    /// Set the generator used for pool aliases.
+    pub fn alias_generator(mut self, generator: AliasGenerator) -> Self {
+        self.generator = generator;
+        self
+    }
+
+    /// Replace inboxes that expire within `margin` (default: 5 minutes).
+    ///
+    /// # Panics
+    /// Panics if `margin` is not shorter than [`INBOX_LIFETIME`].
+    pub fn refresh_margin(mut self, margin: Duration) -> Self {
+        assert!(
+            margin < INBOX_LIFETIME,
+            "refresh margin must be shorter than the inbox lifetime"
+        );
+        self.refresh_margin = margin;
+        self
+    }
+
+    /// How often idle inboxes are checked for upcoming expiry (default: 60 seconds).
+    pub fn refresh_interval(mut self, interval: Duration) -> Self {
+        self.refresh_interval = interval;
+        self
+    }
+
+    /// Stop the pool when `token` is cancelled.
+    ///
+    /// Cancellation ends the background refresh task and makes pending and future
+    /// [`acquire`](InboxPool::acquire) calls return `Error::Cancelled`.
+    pub fn cancel_on(mut self, token: CancellationToken) -> Self {
+        self.cancel = token;
+        self
+    }
+
+    /// Create all inboxes concurrently and start the background refresh task.
+    ///
+    /// Must be called from within a Tokio runtime.
+    ///
+    /// # Errors
+    /// Returns the first error from creating an inbox; inboxes created before it are dropped
+    /// without being forgotten.
+    ///
+    /// # Network
+    /// Issues `size` POST requests to `ajax.php`.
+    pub async fn build(self) -> Result<InboxPool> {
+        let mut tasks = tokio::task::JoinSet::new();
+        for _ in 0..self.size {
+            let client = self.client.clone();
+            let alias = self.generator.generate();
+            tasks.spawn(async move { client.create_inbox(&alias).await });
+        }
+
+        let mut idle = VecDeque::with_capacity(self.size);
+        while let Some(joined) = tasks.join_next().await {
+            let inbox = match joined {
+                Ok(result) => result?,
+                Err(join_err) => std::panic::resume_unwind(join_err.into_panic()),
+            };
+            idle.push_back(inbox);
+        }
+
+        let inner = Arc::new(PoolInner {
+            client: self.client,
+            generator: self.generator,
+            refresh_margin: self.refresh_margin,
+            idle: Mutex::new(idle),
+            lent: Mutex::new(Vec::new()),
+            available: Semaphore::new(self.size),
+            cancel: self.cancel.clone(),
+        });
+
+        tokio::spawn(refresh_loop(
+            Arc::downgrade(&inner),
+            self.refresh_interval,
+            self.cancel,
+        ));
+
+        Ok(InboxPool { inner })
+    }
+}
+
+async fn refresh_loop(pool: Weak<PoolInner>, interval: Duration, cancel: CancellationToken) {
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => {}
+            _ = cancel.cancelled() => return,
+        }
+        let Some(pool) = pool.upgrade() else {
+            return;
+        };
+        pool.refresh_idle().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::Method::POST;
+    use httpmock::MockServer;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn acquire_waits_for_release() {
+        let server = MockServer::start();
+        let base_url = server.base_url();
+
+        let create_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/ajax.php")
+                .query_param("f", "set_email_user");
+            then.status(200)
+                .json_body(json!({ "email_addr": "pooled@example.com" }));
+        });
+
+        let client = Client::new_for_tests(base_url.clone(), format!("{base_url}/ajax.php"));
+        let pool = InboxPool::builder(client).size(2).build().await.unwrap();
+        create_mock.assert_hits(2);
+
+        let first = pool.acquire().await.unwrap();
+        let _second = pool.acquire().await.unwrap();
+        assert_eq!(pool.idle(), 0);
+
+        let blocked = tokio::time::timeout(Duration::from_millis(50), pool.acquire()).await;
+        assert!(blocked.is_err());
+
+        pool.release(first);
+        let again = tokio::time::timeout(Duration::from_millis(50), pool.acquire()).await;
+        assert_eq!(again.unwrap().unwrap().address(), "pooled@example.com");
+        create_mock.assert_hits(2);
+    }
+
+    #[tokio::test]
+    async fn release_ignores_inboxes_the_pool_did_not_lend() {
+        let server = MockServer::start();
+        let base_url = server.base_url();
+
+        server.mock(|when, then| {
+            when.method(POST)
+                .path("/ajax.php")
+                .query_param("f", "set_email_user");
+            then.status(200)
+                .json_body(json!({ "email_addr": "pooled@example.com" }));
+        });
+
+        let client = Client::new_for_tests(base_url.clone(), format!("{base_url}/ajax.php"));
+        let pool = InboxPool::builder(client.clone())
+            .size(1)
+            .build()
+            .await
+            .unwrap();
+
+        let inbox = pool.acquire().await.unwrap();
+        pool.release(inbox.clone());
+        pool.release(inbox);
+        pool.release(client.inbox("other@example.com"));
+        assert_eq!(pool.idle(), 1);
+
+        let _held = pool.acquire().await.unwrap();
+        let blocked = tokio::time::timeout(Duration::from_millis(50), pool.acquire()).await;
+        assert!(blocked.is_err());
+    }
+
+    #[tokio::test]
+    async fn acquire_replaces_stale_inbox() {
+        let server = MockServer::start();
+        let base_url = server.base_url();
+
+        let create_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/ajax.php")
+                .query_param("f", "set_email_user");
+            then.status(200).json_body(json!({
+                "email_addr": "stale@example.com",
+                "email_timestamp": 1
+            }));
+        });
+
+        let client = Client::new_for_tests(base_url.clone(), format!("{base_url}/ajax.php"));
+        let pool = InboxPool::builder(client).size(1).build().await.unwrap();
+
+        pool.acquire().await.unwrap();
+
+        create_mock.assert_hits(2);
+    }
+
+    #[tokio::test]
+    async fn dropping_acquire_during_replacement_keeps_capacity() {
+        let server = MockServer::start();
+        let base_url = server.base_url();
+
+        server.mock(|when, then| {
+            when.method(POST)
+                .path("/ajax.php")
+                .query_param("f", "set_email_user");
+            then.status(200)
+                .delay(Duration::from_millis(200))
+                .json_body(json!({
+                    "email_addr": "stale@example.com",
+                    "email_timestamp": 1
+                }));
+        });
+
+        let client = Client::new_for_tests(base_url.clone(), format!("{base_url}/ajax.php"));
+        let pool = InboxPool::builder(client).size(1).build().await.unwrap();
+
+        let dropped = tokio::time::timeout(Duration::from_millis(50), pool.acquire()).await;
+        assert!(dropped.is_err());
+        assert_eq!(pool.idle(), 1);
+
+        let again = tokio::time::timeout(Duration::from_secs(2), pool.acquire()).await;
+        assert_eq!(again.unwrap().unwrap().address(), "stale@example.com");
+    }
+
+    #[tokio::test]
+    async fn cancellation_releases_blocked_acquire() {
+        let server = MockServer::start();
+        let base_url = server.base_url();
+
+        server.mock(|when, then| {
+            when.method(POST)
+                .path("/ajax.php")
+                .query_param("f", "set_email_user");
+            then.status(200)
+                .json_body(json!({ "email_addr": "pooled@example.com" }));
+        });
+
+        let client = Client::new_for_tests(base_url.clone(), format!("{base_url}/ajax.php"));
+        let token = CancellationToken::new();
+        let pool = InboxPool::builder(client)
+            .size(1)
+            .cancel_on(token.clone())
+            .build()
+            .await
+            .unwrap();
+        let _held = pool.acquire().await.unwrap();
+
+        let blocked = tokio::spawn({
+            let pool = pool.clone();
+            async move { pool.acquire().await }
+        });
+        token.cancel();
+        let result = tokio::time::timeout(Duration::from_secs(1), blocked).await;
+
+        assert!(matches!(result, Ok(Ok(Err(Error::Cancelled)))));
+    }
+}
@@ -0,0 +1,101 @@
+//! Browser header presets.
+
+/// A set of request headers matching one browser, for [`ClientBuilder::header_profile`].
+///
+/// Every AJAX request carries the headers a browser's XHR would send, so a user agent claiming
+/// one browser alongside another browser's `Accept-Language`, `Priority`, or missing client
+/// hints is an easy fingerprint. A profile keeps them consistent: it sets the user agent,
+/// `Accept`, and `Accept-Language`, plus browser-specific headers such as Chrome's `Sec-CH-UA-*`
+/// client hints. The `Sec-Fetch-*` values describe a same-origin XHR and are sent with every
+/// profile.
+///
+/// Versioned constructors such as [`firefox_131`](HeaderProfile::firefox_131) never change;
+/// the `*_latest` constructors track the newest preset in this crate and may move to newer
+/// browser versions in minor releases.
+///
+/// [`ClientBuilder::header_profile`]: crate::ClientBuilder::header_profile
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HeaderProfile {
+    user_agent: &'static str,
+    accept_language: &'static str,
+    extra: &'static [(&'static str, &'static str)],
+}
+
+/// `Accept` sent by jQuery's `$.getJSON`, identical across browsers.
+pub(crate) const XHR_ACCEPT: &str = "application/json, text/javascript, */*; q=0.01";
+
+impl Default for HeaderProfile {
+    fn default() -> Self {
+        Self::firefox_131()
+    }
+}
+
+impl HeaderProfile {
+    /// Firefox 131 on Windows (the default).
+    pub fn firefox_131() -> Self {
+        Self {
+            user_agent: "Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:131.0) Gecko/20100101 \
+                         Firefox/131.0",
+            accept_language: "en-US,en;q=0.5",
+            extra: &[("Priority", "u=0")],
+        }
+    }
+
+    /// Chrome 130 on Windows.
+    pub fn chrome_130() -> Self {
+        Self {
+            user_agent: "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, \
+                         like Gecko) Chrome/130.0.0.0 Safari/537.36",
+            accept_language: "en-US,en;q=0.9",
+            extra: &[
+                (
+                    "Sec-CH-UA",
+                    r#""Chromium";v="130", "Google Chrome";v="130", "Not?A_Brand";v="99""#,
+                ),
+                ("Sec-CH-UA-Mobile", "?0"),
+                ("Sec-CH-UA-Platform", r#""Windows""#),
+                ("Priority", "u=1, i"),
+            ],
+        }
+    }
+
+    /// Safari 18 on macOS.
+    pub fn safari_18() -> Self {
+        Self {
+            user_agent: "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/605.1.15 \
+                         (KHTML, like Gecko) Version/18.0 Safari/605.1.15",
+            accept_language: "en-US,en;q=0.9",
+            extra: &[("Priority", "u=3, i")],
+        }
+    }
+
+    /// The newest Firefox preset, currently [`firefox_131`](HeaderProfile::firefox_131).
+    pub fn firefox_latest() -> Self {
+        Self::firefox_131()
+    }
+
+    /// The newest Chrome preset, currently [`chrome_130`](HeaderProfile::chrome_130).
+    pub fn chrome_latest() -> Self {
+        Self::chrome_130()
+    }
+
+    /// The newest Safari preset, currently [`safari_18`](HeaderProfile::safari_18).
+    pub fn safari_latest() -> Self {
+        Self::safari_18()
+    }
+
+    /// User agent sent with this profile.
+    pub fn user_agent(&self) -> &str {
+        self.user_agent
+    }
+
+    /// `Accept-Language` sent with this profile.
+    pub(crate) fn accept_language(&self) -> &'static str {
+        self.accept_language
+    }
+
+    /// Browser-specific headers sent in addition to the common set.
+    pub(crate) fn extra(&self) -> &'static [(&'static str, &'static str)] {
+        self.extra
+    }
+}
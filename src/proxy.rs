@@ -0,0 +1,330 @@
+//! Typed proxy configuration and proxy pool rotation.
+
+use crate::{Error, Result};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Proxy to route all GuerrillaMail traffic through, for [`ClientBuilder::proxy_config`].
+///
+/// Addresses are `host:port` without a scheme or credentials; supply credentials with
+/// [`ClientBuilder::proxy_auth`].
+///
+/// # DNS
+/// With an HTTP(S) proxy, HTTPS requests are tunnelled with `CONNECT host:port`, so the proxy
+/// resolves GuerrillaMail's hostname. With SOCKS5 it depends on `remote_dns`: when `true`
+/// (`socks5h://`) the hostname is sent to the proxy; when `false` (`socks5://`) it is resolved
+/// locally first, which leaks the lookup to the local resolver.
+///
+/// [`ClientBuilder::proxy_config`]: crate::ClientBuilder::proxy_config
+/// [`ClientBuilder::proxy_auth`]: crate::ClientBuilder::proxy_auth
+///
+/// # Example
+/// ```
+/// use guerrillamail_client::ProxyConfig;
+///
+/// let proxy: ProxyConfig = "socks5h://127.0.0.1:9050".parse()?;
+/// assert_eq!(proxy, ProxyConfig::socks5("127.0.0.1:9050"));
+/// assert!(proxy.remote_dns());
+/// # Ok::<(), guerrillamail_client::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ProxyConfig {
+    /// Plain HTTP proxy (`http://`).
+    Http(String),
+    /// HTTP proxy reached over TLS (`https://`).
+    Https(String),
+    /// SOCKS5 proxy (`socks5://` or, with `remote_dns`, `socks5h://`).
+    Socks5 {
+        /// Proxy address as `host:port`.
+        addr: String,
+        /// Let the proxy resolve hostnames instead of the local resolver.
+        remote_dns: bool,
+    },
+}
+
+impl ProxyConfig {
+    /// HTTP proxy at `addr` (`host:port`).
+    pub fn http(addr: impl Into<String>) -> Self {
+        Self::Http(addr.into())
+    }
+
+    /// SOCKS5 proxy at `addr` (`host:port`) that resolves hostnames remotely (`socks5h://`).
+    pub fn socks5(addr: impl Into<String>) -> Self {
+        Self::Socks5 {
+            addr: addr.into(),
+            remote_dns: true,
+        }
+    }
+
+    /// SOCKS5 proxy at `addr` (`host:port`) with hostnames resolved locally (`socks5://`).
+    pub fn socks5_local_dns(addr: impl Into<String>) -> Self {
+        Self::Socks5 {
+            addr: addr.into(),
+            remote_dns: false,
+        }
+    }
+
+    /// SOCKS5 proxy to a local Tor instance at `127.0.0.1:9050`, resolving hostnames through Tor.
+    #[cfg(feature = "tor")]
+    pub fn tor() -> Self {
+        Self::socks5(TOR_SOCKS_ADDR)
+    }
+
+    /// Whether hostnames are resolved by the proxy rather than locally.
+    pub fn remote_dns(&self) -> bool {
+        match self {
+            Self::Http(_) | Self::Https(_) => true,
+            Self::Socks5 { remote_dns, .. } => *remote_dns,
+        }
+    }
+
+    /// Proxy address as `host:port`.
+    pub fn addr(&self) -> &str {
+        match self {
+            Self::Http(addr) | Self::Https(addr) | Self::Socks5 { addr, .. } => addr,
+        }
+    }
+
+    /// The proxy as a URL understood by reqwest, e.g. `socks5h://127.0.0.1:9050`.
+    pub fn to_url(&self) -> String {
+        let scheme = match self {
+            Self::Http(_) => "http",
+            Self::Https(_) => "https",
+            Self::Socks5 {
+                remote_dns: true, ..
+            } => "socks5h",
+            Self::Socks5 {
+                remote_dns: false, ..
+            } => "socks5",
+        };
+        format!("{scheme}://{}", self.addr())
+    }
+}
+
+impl fmt::Display for ProxyConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_url())
+    }
+}
+
+impl FromStr for ProxyConfig {
+    type Err = Error;
+
+    /// Parse `http://`, `https://`, `socks5://`, or `socks5h://` URLs of the form
+    /// `scheme://host:port`.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidProxy` for other schemes, missing ports, embedded credentials, or
+    /// trailing paths.
+    fn from_str(url: &str) -> Result<Self> {
+        let invalid = |reason: &str| Error::InvalidProxy(format!("{url}: {reason}"));
+        let (scheme, addr) = url
+            .split_once("://")
+            .ok_or_else(|| invalid("missing scheme"))?;
+        let addr = addr.strip_suffix('/').unwrap_or(addr);
+        if addr.contains('@') {
+            return Err(invalid("use ClientBuilder::proxy_auth for credentials"));
+        }
+        if addr.contains('/') {
+            return Err(invalid("unexpected path"));
+        }
+        let port = addr.rsplit_once(':').map(|(_, port)| port);
+        if port.is_none_or(|p| p.parse::<u16>().is_err()) {
+            return Err(invalid("expected host:port"));
+        }
+
+        match scheme.to_ascii_lowercase().as_str() {
+            "http" => Ok(Self::http(addr)),
+            "https" => Ok(Self::Https(addr.to_string())),
+            "socks5" => Ok(Self::socks5_local_dns(addr)),
+            "socks5h" => Ok(Self::socks5(addr)),
+            _ => Err(invalid("unsupported scheme")),
+        }
+    }
+}
+
+/// Default SOCKS port of a local Tor daemon.
+#[cfg(feature = "tor")]
+const TOR_SOCKS_ADDR: &str = "127.0.0.1:9050";
+
+/// Fresh SOCKS username for Tor stream isolation.
+///
+/// Tor's `IsolateSOCKSAuth` (on by default) keeps streams with different SOCKS credentials on
+/// different circuits, so a new username means a new circuit and exit IP.
+#[cfg(feature = "tor")]
+pub(crate) fn tor_isolation_username() -> String {
+    format!("guerrillamail-{:016x}", rand::random::<u64>())
+}
+
+/// When a client configured with [`ClientBuilder::proxy_pool`] moves to the next proxy.
+///
+/// [`ClientBuilder::proxy_pool`]: crate::ClientBuilder::proxy_pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RotationStrategy {
+    /// Send each request through the next proxy in the pool.
+    RoundRobin,
+    /// Stay on one proxy until a request through it fails with a network error or is rate
+    /// limited (`Error::Request` or `Error::RateLimited`), then move to the next.
+    OnFailure,
+}
+
+/// One `reqwest::Client` per proxy, shared by every clone of a client.
+#[derive(Debug)]
+pub(crate) struct ProxyRotation {
+    /// HTTP clients and their proxy URLs (passwords redacted), in pool order.
+    proxies: Vec<(reqwest::Client, String)>,
+    strategy: RotationStrategy,
+    current: AtomicUsize,
+}
+
+impl ProxyRotation {
+    /// # Panics
+    /// Panics if `proxies` is empty.
+    pub(crate) fn new(proxies: Vec<(reqwest::Client, String)>, strategy: RotationStrategy) -> Self {
+        assert!(!proxies.is_empty(), "proxy pool must not be empty");
+        Self {
+            proxies,
+            strategy,
+            current: AtomicUsize::new(0),
+        }
+    }
+
+    /// Pick the proxy for the next request, returning its index and HTTP client.
+    pub(crate) fn select(&self) -> (usize, &reqwest::Client) {
+        let index = match self.strategy {
+            RotationStrategy::RoundRobin => self.current.fetch_add(1, Ordering::Relaxed),
+            RotationStrategy::OnFailure => self.current.load(Ordering::Relaxed),
+        } % self.proxies.len();
+        (index, &self.proxies[index].0)
+    }
+
+    /// URL of the proxy the next request will use.
+    pub(crate) fn peek(&self) -> &str {
+        let index = self.current.load(Ordering::Relaxed) % self.proxies.len();
+        &self.proxies[index].1
+    }
+
+    /// Record the outcome of a request sent through the proxy at `index`.
+    ///
+    /// With [`RotationStrategy::OnFailure`], a network error or rate limit moves the pool past
+    /// `index`; concurrent failures through the same proxy rotate only once.
+    pub(crate) fn record<T>(&self, index: usize, result: &Result<T>) {
+        if self.strategy != RotationStrategy::OnFailure
+            || !matches!(
+                result,
+                Err(Error::Request { .. } | Error::RateLimited { .. })
+            )
+        {
+            return;
+        }
+        let next = (index + 1) % self.proxies.len();
+        let _ = self
+            .current
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |current| {
+                (current % self.proxies.len() == index).then_some(next)
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::session_client;
+    use httpmock::Method::GET;
+    use httpmock::MockServer;
+    use serde_json::json;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+
+    #[test]
+    fn parse_round_trips_supported_schemes() {
+        for url in [
+            "http://proxy:3128",
+            "https://proxy:443",
+            "socks5://127.0.0.1:1080",
+            "socks5h://[::1]:9050",
+        ] {
+            let proxy: ProxyConfig = url.parse().unwrap();
+            assert_eq!(proxy.to_url(), url);
+        }
+
+        assert!(!"socks5://h:1".parse::<ProxyConfig>().unwrap().remote_dns());
+        for bad in [
+            "proxy:3128",
+            "ftp://h:1",
+            "http://u:p@h:1",
+            "http://h",
+            "http://h:1/x",
+        ] {
+            assert!(
+                matches!(bad.parse::<ProxyConfig>(), Err(Error::InvalidProxy(_))),
+                "{bad}"
+            );
+        }
+    }
+
+    /// Accept one SOCKS5 CONNECT, report the requested address type, and relay to `target`.
+    async fn socks5_relay(listener: TcpListener, target: std::net::SocketAddr) -> u8 {
+        let (mut client, _) = listener.accept().await.unwrap();
+
+        let mut greeting = [0u8; 2];
+        client.read_exact(&mut greeting).await.unwrap();
+        let mut methods = vec![0u8; greeting[1] as usize];
+        client.read_exact(&mut methods).await.unwrap();
+        client.write_all(&[5, 0]).await.unwrap();
+
+        let mut header = [0u8; 4];
+        client.read_exact(&mut header).await.unwrap();
+        let address_type = header[3];
+        let address_len = match address_type {
+            1 => 4,
+            4 => 16,
+            3 => client.read_u8().await.unwrap() as usize,
+            other => panic!("unexpected address type {other}"),
+        };
+        let mut rest = vec![0u8; address_len + 2];
+        client.read_exact(&mut rest).await.unwrap();
+        client
+            .write_all(&[5, 0, 0, 1, 0, 0, 0, 0, 0, 0])
+            .await
+            .unwrap();
+
+        let mut upstream = TcpStream::connect(target).await.unwrap();
+        tokio::spawn(async move {
+            let _ = tokio::io::copy_bidirectional(&mut client, &mut upstream).await;
+        });
+        address_type
+    }
+
+    async fn address_type_sent_through(proxy: fn(String) -> ProxyConfig) -> u8 {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET).path("/ajax.php");
+            then.status(200).json_body(json!({ "list": [] }));
+        });
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let proxy_addr = listener.local_addr().unwrap().to_string();
+        let relay = tokio::spawn(socks5_relay(listener, *server.address()));
+
+        let base_url = format!("http://localhost:{}", server.port());
+        let client = session_client(&base_url)
+            .proxy_config(proxy(proxy_addr))
+            .build()
+            .await
+            .unwrap();
+        client.get_messages("alias@example.com").await.unwrap();
+
+        relay.await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn socks5h_sends_hostname_and_socks5_resolves_locally() {
+        // 3 = domain name; local resolution sends an IPv4 (1) or IPv6 (4) address instead.
+        assert_eq!(address_type_sent_through(ProxyConfig::socks5).await, 3);
+        assert_ne!(
+            address_type_sent_through(ProxyConfig::socks5_local_dns).await,
+            3
+        );
+    }
+}
@@ -0,0 +1,95 @@
+//! Client-side token bucket pacing requests to GuerrillaMail.
+
+use crate::Timer;
+use std::sync::Mutex;
+use std::time::Duration;
+use web_time::Instant;
+
+/// Token bucket shared by all clones of a client.
+///
+/// Tokens refill continuously at `rate` per second up to a burst of `max(rate, 1)`; each request
+/// takes one token and waits until one is available.
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    rate: f64,
+    capacity: f64,
+    bucket: Mutex<Bucket>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl RateLimiter {
+    /// # Panics
+    /// Panics if `requests_per_second` is not a positive, finite number.
+    pub(crate) fn new(requests_per_second: f64) -> Self {
+        assert!(
+            requests_per_second.is_finite() && requests_per_second > 0.0,
+            "rate limit must be a positive number of requests per second"
+        );
+        let capacity = requests_per_second.max(1.0);
+        Self {
+            rate: requests_per_second,
+            capacity,
+            bucket: Mutex::new(Bucket {
+                tokens: capacity,
+                refilled_at: Instant::now(),
+            }),
+        }
+    }
+
+    /// Wait until a request may be sent, then take a token.
+    pub(crate) async fn acquire(&self, timer: &dyn Timer) {
+        while let Some(wait) = self.try_acquire() {
+            timer.sleep(wait).await;
+        }
+    }
+
+    /// Take a token if one is available; otherwise return how long until one will be.
+    fn try_acquire(&self) -> Option<Duration> {
+        let mut bucket = self.bucket.lock().expect("rate limiter lock poisoned");
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.rate).min(self.capacity);
+        bucket.refilled_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            None
+        } else {
+            Some(Duration::from_secs_f64((1.0 - bucket.tokens) / self.rate))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allows_a_burst_then_reports_wait() {
+        let limiter = RateLimiter::new(2.0);
+
+        assert!(limiter.try_acquire().is_none());
+        assert!(limiter.try_acquire().is_none());
+        let wait = limiter.try_acquire().expect("bucket should be empty");
+
+        assert!(wait > Duration::from_millis(400) && wait <= Duration::from_millis(500));
+    }
+
+    #[tokio::test]
+    async fn acquire_paces_requests() {
+        let limiter = RateLimiter::new(20.0);
+        let start = Instant::now();
+
+        for _ in 0..25 {
+            limiter.acquire(&crate::TokioTimer).await;
+        }
+
+        // 20 tokens are available immediately; the remaining 5 take ~50ms each.
+        assert!(start.elapsed() >= Duration::from_millis(200));
+    }
+}
@@ -0,0 +1,135 @@
+//! IP version preference applied on top of DNS resolution.
+
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+/// Which IP versions the client connects over, for [`ClientBuilder::ip_preference`].
+///
+/// Applies to hostnames resolved by the client; addresses from [`ClientBuilder::resolve`] and
+/// IP literals in URLs are used as given.
+///
+/// [`ClientBuilder::ip_preference`]: crate::ClientBuilder::ip_preference
+/// [`ClientBuilder::resolve`]: crate::ClientBuilder::resolve
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum IpPreference {
+    /// Use addresses in the order the resolver returns them (default).
+    #[default]
+    Any,
+    /// Try IPv4 addresses first and fall back to IPv6.
+    PreferIpv4,
+    /// Try IPv6 addresses first and fall back to IPv4.
+    PreferIpv6,
+    /// Connect over IPv4 only.
+    Ipv4Only,
+    /// Connect over IPv6 only.
+    Ipv6Only,
+}
+
+impl IpPreference {
+    /// Filter and order resolved addresses; the sort is stable, so resolver order is kept
+    /// within each family.
+    fn apply(self, addrs: impl Iterator<Item = SocketAddr>) -> Vec<SocketAddr> {
+        let mut addrs: Vec<_> = match self {
+            Self::Ipv4Only => addrs.filter(SocketAddr::is_ipv4).collect(),
+            Self::Ipv6Only => addrs.filter(SocketAddr::is_ipv6).collect(),
+            _ => addrs.collect(),
+        };
+        match self {
+            Self::PreferIpv4 => addrs.sort_by_key(|addr| !addr.is_ipv4()),
+            Self::PreferIpv6 => addrs.sort_by_key(|addr| !addr.is_ipv6()),
+            _ => {}
+        }
+        addrs
+    }
+}
+
+/// Resolver that applies an [`IpPreference`] to another resolver, or to the system resolver.
+pub(crate) struct PreferenceResolver {
+    inner: Option<Arc<dyn Resolve>>,
+    preference: IpPreference,
+}
+
+impl PreferenceResolver {
+    pub(crate) fn new(inner: Option<Arc<dyn Resolve>>, preference: IpPreference) -> Self {
+        Self { inner, preference }
+    }
+}
+
+impl Resolve for PreferenceResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let inner = self.inner.clone();
+        let preference = self.preference;
+        Box::pin(async move {
+            let host = name.as_str().to_string();
+            let addrs: Vec<_> = match inner {
+                Some(inner) => inner.resolve(name).await?.collect(),
+                None => tokio::net::lookup_host((host.as_str(), 0)).await?.collect(),
+            };
+            let addrs = preference.apply(addrs.into_iter());
+            if addrs.is_empty() {
+                let message = format!("{host} has no addresses allowed by {preference:?}");
+                return Err(io::Error::new(io::ErrorKind::NotFound, message).into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Error;
+    use crate::client::session_client;
+    use httpmock::Method::GET;
+    use httpmock::MockServer;
+    use serde_json::json;
+
+    #[test]
+    fn apply_orders_and_filters_by_family() {
+        let v4: SocketAddr = "192.0.2.1:0".parse().unwrap();
+        let v6: SocketAddr = "[2001:db8::1]:0".parse().unwrap();
+        let addrs = || [v6, v4].into_iter();
+
+        assert_eq!(IpPreference::Any.apply(addrs()), [v6, v4]);
+        assert_eq!(IpPreference::PreferIpv4.apply(addrs()), [v4, v6]);
+        assert_eq!(IpPreference::PreferIpv6.apply(addrs()), [v6, v4]);
+        assert_eq!(IpPreference::Ipv4Only.apply(addrs()), [v4]);
+        assert_eq!(IpPreference::Ipv6Only.apply(addrs()), [v6]);
+    }
+
+    struct Fixed(Vec<SocketAddr>);
+
+    impl Resolve for Fixed {
+        fn resolve(&self, _name: Name) -> Resolving {
+            let addrs: Addrs = Box::new(self.0.clone().into_iter());
+            Box::pin(async move { Ok(addrs) })
+        }
+    }
+
+    #[tokio::test]
+    async fn client_skips_excluded_families() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET).path("/ajax.php");
+            then.status(200).json_body(json!({ "list": [] }));
+        });
+        let base_url = format!("http://guerrillamail.invalid:{}", server.port());
+        let client = |preference| {
+            // Nothing listens on the IPv6 address, so only IPv4 can connect.
+            let resolver = Fixed(vec!["[::1]:0".parse().unwrap(), *server.address()]);
+            session_client(&base_url)
+                .dns_resolver(Arc::new(resolver))
+                .ip_preference(preference)
+                .build()
+        };
+
+        let v4 = client(IpPreference::Ipv4Only).await.unwrap();
+        v4.get_messages("alias@example.com").await.unwrap();
+
+        let v6 = client(IpPreference::Ipv6Only).await.unwrap();
+        let err = v6.get_messages("alias@example.com").await.unwrap_err();
+        assert!(matches!(err, Error::Request { .. }));
+    }
+}
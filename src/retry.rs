@@ -0,0 +1,152 @@
+//! Retry policy for idempotent API calls.
+
+use crate::Error;
+use rand::Rng;
+use std::time::Duration;
+
+const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+const DEFAULT_INITIAL_BACKOFF: Duration = Duration::from_millis(200);
+const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(5);
+
+/// How idempotent calls are retried after transient failures.
+///
+/// Applied by the client to read-only calls such as [`Client::get_messages`],
+/// [`Client::fetch_email`], and [`Client::current_address`] when configured with
+/// [`ClientBuilder::retry`]. Calls that change server state are never retried.
+///
+/// Network errors (connect failures, timeouts) and 5xx responses are retried; other errors,
+/// including `Error::RateLimited` (see [`ClientBuilder::retry_after_budget`]), are returned
+/// immediately. The wait before attempt `n + 1` is `initial_backoff * 2^(n - 1)`, capped at
+/// `max_backoff`; with jitter enabled a random value between half and all of that is used.
+///
+/// [`Client::get_messages`]: crate::Client::get_messages
+/// [`Client::fetch_email`]: crate::Client::fetch_email
+/// [`Client::current_address`]: crate::Client::current_address
+/// [`ClientBuilder::retry`]: crate::ClientBuilder::retry
+/// [`ClientBuilder::retry_after_budget`]: crate::ClientBuilder::retry_after_budget
+///
+/// # Example
+/// ```no_run
+/// # use guerrillamail_client::{Client, RetryPolicy};
+/// # use std::time::Duration;
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), guerrillamail_client::Error> {
+/// let client = Client::builder()
+///     .retry(RetryPolicy::new().max_attempts(5).initial_backoff(Duration::from_millis(500)))
+///     .build()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RetryPolicy {
+    /// Create a policy with 3 attempts, 200ms initial backoff, 5s maximum backoff, and jitter.
+    pub fn new() -> Self {
+        Self {
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            initial_backoff: DEFAULT_INITIAL_BACKOFF,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+            jitter: true,
+        }
+    }
+
+    /// Set the total number of attempts, including the first (default: 3).
+    ///
+    /// # Panics
+    /// Panics if `attempts` is zero.
+    pub fn max_attempts(mut self, attempts: u32) -> Self {
+        assert!(attempts > 0, "retry policy needs at least one attempt");
+        self.max_attempts = attempts;
+        self
+    }
+
+    /// Set the wait before the first retry (default: 200ms).
+    pub fn initial_backoff(mut self, backoff: Duration) -> Self {
+        self.initial_backoff = backoff;
+        self
+    }
+
+    /// Set the upper bound for a single wait (default: 5s).
+    pub fn max_backoff(mut self, backoff: Duration) -> Self {
+        self.max_backoff = backoff;
+        self
+    }
+
+    /// Randomize each wait to avoid synchronized retries from parallel clients (default: `true`).
+    pub fn jitter(mut self, enabled: bool) -> Self {
+        self.jitter = enabled;
+        self
+    }
+
+    /// Total number of attempts, including the first.
+    pub(crate) fn attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Wait before retrying after `attempt` (1-based) failed.
+    pub(crate) fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff);
+        if !self.jitter || backoff.is_zero() {
+            return backoff;
+        }
+        let half = backoff / 2;
+        half + half.mul_f64(rand::rng().random_range(0.0..=1.0))
+    }
+
+    /// Whether `err` is a transient failure worth another attempt.
+    pub(crate) fn is_retryable(err: &Error) -> bool {
+        match err {
+            #[cfg(not(target_arch = "wasm32"))]
+            Error::Request { source: e, .. } => e.is_timeout() || e.is_connect() || e.is_request(),
+            #[cfg(target_arch = "wasm32")]
+            Error::Request { source: e, .. } => e.is_timeout() || e.is_request(),
+            Error::Api { status, .. } => status.is_server_error(),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        let policy = RetryPolicy::new()
+            .initial_backoff(Duration::from_millis(100))
+            .max_backoff(Duration::from_millis(350))
+            .jitter(false);
+
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(350));
+        assert_eq!(policy.backoff(40), Duration::from_millis(350));
+    }
+
+    #[test]
+    fn jitter_stays_between_half_and_full_backoff() {
+        let policy = RetryPolicy::new().initial_backoff(Duration::from_millis(100));
+
+        for _ in 0..100 {
+            let backoff = policy.backoff(1);
+            assert!(backoff >= Duration::from_millis(50) && backoff <= Duration::from_millis(100));
+        }
+    }
+}
@@ -0,0 +1,141 @@
+//! Pluggable timer for waits in the request path.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
+/// Future returned by [`Timer::sleep`].
+#[cfg(not(target_arch = "wasm32"))]
+pub type SleepFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// Future returned by [`Timer::sleep`]; not `Send` on wasm32, where JavaScript timers are not.
+#[cfg(target_arch = "wasm32")]
+pub type SleepFuture = Pin<Box<dyn Future<Output = ()>>>;
+
+/// Sleeps on behalf of a [`Client`], for [`ClientBuilder::timer`].
+///
+/// The request methods wait only for rate limiting, retry backoff, and `Retry-After`; those
+/// waits go through the timer, and everything else in the request path is executor-neutral.
+/// Together with a [`Transport`] that does not need Tokio, a custom timer lets the plain
+/// request methods run on async-std, smol, or any other executor. The default,
+/// [`TokioTimer`], and the default reqwest transport both require a Tokio runtime, as do the
+/// background helpers behind the `tokio` feature (`Inbox::keep_alive`, watchers, and
+/// `InboxPool`), which spawn tasks.
+///
+/// [`Client`]: crate::Client
+/// [`ClientBuilder::timer`]: crate::ClientBuilder::timer
+/// [`Transport`]: crate::Transport
+///
+/// # Example
+/// ```
+/// use guerrillamail_client::{SleepFuture, Timer};
+/// use std::time::Duration;
+///
+/// /// Sleeps on a dedicated thread, with no executor involved.
+/// struct ThreadTimer;
+///
+/// impl Timer for ThreadTimer {
+///     fn sleep(&self, duration: Duration) -> SleepFuture {
+///         let (tx, rx) = tokio::sync::oneshot::channel();
+///         std::thread::spawn(move || {
+///             std::thread::sleep(duration);
+///             let _ = tx.send(());
+///         });
+///         Box::pin(async move {
+///             let _ = rx.await;
+///         })
+///     }
+/// }
+/// ```
+pub trait Timer: Send + Sync {
+    /// Complete after `duration`.
+    fn sleep(&self, duration: Duration) -> SleepFuture;
+}
+
+/// Sleeps with `tokio::time::sleep` (the default).
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokioTimer;
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Timer for TokioTimer {
+    fn sleep(&self, duration: Duration) -> SleepFuture {
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
+
+/// Sleeps with JavaScript's `setTimeout` (the default on wasm32).
+#[cfg(target_arch = "wasm32")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsTimer;
+
+#[cfg(target_arch = "wasm32")]
+impl Timer for JsTimer {
+    fn sleep(&self, duration: Duration) -> SleepFuture {
+        Box::pin(gloo_timers::future::sleep(duration))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::test_session;
+    use crate::{Client, Transport, TransportFuture};
+    use std::sync::{Arc, Mutex};
+    use std::task::{Context, Poll, Waker};
+
+    /// Records requested sleeps and blocks the calling thread for them.
+    #[derive(Default)]
+    struct ThreadSleep(Mutex<Vec<Duration>>);
+
+    impl Timer for Arc<ThreadSleep> {
+        fn sleep(&self, duration: Duration) -> SleepFuture {
+            self.0.lock().unwrap().push(duration);
+            std::thread::sleep(duration);
+            Box::pin(std::future::ready(()))
+        }
+    }
+
+    struct EmptyInbox;
+
+    impl Transport for EmptyInbox {
+        fn send(&self, _request: reqwest::Request) -> TransportFuture<'_> {
+            Box::pin(async { Ok(http::Response::new(r#"{"list":[]}"#).into()) })
+        }
+    }
+
+    /// Poll `future` to completion with no executor or reactor at all.
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = std::pin::pin!(future);
+        let mut cx = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+        }
+    }
+
+    #[test]
+    fn request_methods_run_without_a_tokio_runtime() {
+        let timer = Arc::new(ThreadSleep::default());
+        let client = block_on(
+            Client::builder()
+                .transport(EmptyInbox)
+                .timer(Arc::clone(&timer))
+                .rate_limit(20.0)
+                .session(test_session())
+                .build(),
+        )
+        .unwrap();
+
+        // 20 requests use up the burst; the 21st waits for the timer.
+        for _ in 0..21 {
+            let messages = block_on(client.get_messages("alias@example.com")).unwrap();
+            assert!(messages.is_empty());
+        }
+
+        let sleeps = timer.0.lock().unwrap();
+        assert!(!sleeps.is_empty());
+        assert!(sleeps.iter().all(|wait| *wait <= Duration::from_millis(50)));
+    }
+}
@@ -0,0 +1,119 @@
+//! Queries for [`Client::search`](crate::Client::search).
+
+use crate::{EmailDetails, Message};
+use regex::Regex;
+
+/// What [`Client::search`](crate::Client::search) looks for, and where.
+///
+/// A query matches the subject and sender by default; [`bodies`](Query::bodies) extends it to
+/// the plain-text message bodies, which costs one `fetch_email` per message.
+///
+/// # Example
+/// ```
+/// use guerrillamail_client::Query;
+///
+/// let query = Query::contains("verify").sender(false).bodies(true).concurrency(8);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Query {
+    pattern: Pattern,
+    subject: bool,
+    sender: bool,
+    bodies: bool,
+    concurrency: usize,
+}
+
+#[derive(Debug, Clone)]
+enum Pattern {
+    /// Lowercased needle, matched case-insensitively.
+    Contains(String),
+    Regex(Regex),
+}
+
+impl Query {
+    /// Match text containing `needle`, ignoring case.
+    pub fn contains(needle: impl AsRef<str>) -> Self {
+        Self::new(Pattern::Contains(needle.as_ref().to_lowercase()))
+    }
+
+    /// Match text in which `regex` finds a match.
+    pub fn regex(regex: Regex) -> Self {
+        Self::new(Pattern::Regex(regex))
+    }
+
+    fn new(pattern: Pattern) -> Self {
+        Self {
+            pattern,
+            subject: true,
+            sender: true,
+            bodies: false,
+            concurrency: 4,
+        }
+    }
+
+    /// Whether to match against the subject (default: `true`).
+    pub fn subject(mut self, enabled: bool) -> Self {
+        self.subject = enabled;
+        self
+    }
+
+    /// Whether to match against the sender (default: `true`).
+    pub fn sender(mut self, enabled: bool) -> Self {
+        self.sender = enabled;
+        self
+    }
+
+    /// Whether to fetch and match against message bodies as plain text (default: `false`).
+    ///
+    /// Only messages whose subject and sender do not already match are fetched.
+    pub fn bodies(mut self, enabled: bool) -> Self {
+        self.bodies = enabled;
+        self
+    }
+
+    /// Set the maximum number of body fetches in flight at once (default: 4).
+    ///
+    /// # Panics
+    /// Panics if `limit` is zero.
+    pub fn concurrency(mut self, limit: usize) -> Self {
+        assert!(limit > 0, "search concurrency must be non-zero");
+        self.concurrency = limit;
+        self
+    }
+
+    pub(crate) fn searches_bodies(&self) -> bool {
+        self.bodies
+    }
+
+    pub(crate) fn limit(&self) -> usize {
+        self.concurrency
+    }
+
+    /// Whether the enabled header fields of `message` match.
+    pub(crate) fn matches_headers(&self, message: &Message) -> bool {
+        (self.subject && self.is_match(&message.mail_subject))
+            || (self.sender && self.is_match(&message.mail_from))
+    }
+
+    /// Whether the plain-text body of `details` matches.
+    pub(crate) fn matches_body(&self, details: &EmailDetails) -> bool {
+        self.is_match(&details.body_text())
+    }
+
+    fn is_match(&self, text: &str) -> bool {
+        match &self.pattern {
+            Pattern::Contains(needle) => text.to_lowercase().contains(needle.as_str()),
+            Pattern::Regex(regex) => regex.is_match(text),
+        }
+    }
+}
+
+/// A message found by [`Client::search`](crate::Client::search).
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct SearchHit {
+    /// The message header from the inbox listing.
+    pub message: Message,
+    /// The full message, when it matched on its body; `None` when its subject or sender matched.
+    pub details: Option<EmailDetails>,
+}
@@ -0,0 +1,136 @@
+//! `tower::Service` view of the client's request path.
+
+use crate::{Client, Error};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// A raw GuerrillaMail AJAX call, sent through a [`Client`] used as a `tower::Service`.
+///
+/// Names the AJAX function (`f=`) and its parameters. For `GET` calls the parameters go in the
+/// query string together with a cache-busting `_` timestamp; for `POST` calls they are sent as
+/// a form body. The client adds `f`, its headers, and the API token, but no other parameters,
+/// so supply `site`, `in`, and similar values yourself.
+///
+/// # Example
+/// ```no_run
+/// use guerrillamail_client::{ApiRequest, Client};
+/// use std::time::Duration;
+/// use tower::{Service, ServiceBuilder, ServiceExt};
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+/// let client = Client::new().await?;
+/// let mut service = ServiceBuilder::new()
+///     .timeout(Duration::from_secs(10))
+///     .service(client);
+///
+/// let request = ApiRequest::get("check_email").param("seq", "1").param("in", "demo");
+/// let response = service.ready().await?.call(request).await?;
+/// println!("{response}");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiRequest {
+    pub(crate) function: String,
+    pub(crate) params: Vec<(String, String)>,
+    pub(crate) post: bool,
+}
+
+impl ApiRequest {
+    /// A `GET` call to the AJAX function `function`, e.g. `"check_email"`.
+    pub fn get(function: impl Into<String>) -> Self {
+        Self {
+            function: function.into(),
+            params: Vec::new(),
+            post: false,
+        }
+    }
+
+    /// A `POST` call to the AJAX function `function`, e.g. `"set_email_user"`.
+    pub fn post(function: impl Into<String>) -> Self {
+        Self {
+            post: true,
+            ..Self::get(function)
+        }
+    }
+
+    /// Add a parameter.
+    pub fn param(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.params.push((key.into(), value.into()));
+        self
+    }
+
+    /// The AJAX function being called.
+    pub fn function(&self) -> &str {
+        &self.function
+    }
+}
+
+/// Sends [`ApiRequest`]s and returns the decoded JSON response.
+///
+/// Requests go through the client's headers, rate limiter, circuit breaker, and automatic
+/// re-authentication. The client's own retry policy and rate-limit waiting are not applied;
+/// compose tower layers for those instead. The service is always ready.
+impl tower::Service<ApiRequest> for Client {
+    type Response = serde_json::Value;
+    type Error = Error;
+    #[cfg(not(target_arch = "wasm32"))]
+    type Future = Pin<Box<dyn Future<Output = Result<serde_json::Value, Error>> + Send>>;
+    #[cfg(target_arch = "wasm32")]
+    type Future = Pin<Box<dyn Future<Output = Result<serde_json::Value, Error>>>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: ApiRequest) -> Self::Future {
+        let client = self.clone();
+        Box::pin(async move { client.call_api(&request).await })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::Method::{GET, POST};
+    use httpmock::MockServer;
+    use serde_json::json;
+    use tower::{ServiceBuilder, ServiceExt};
+
+    #[tokio::test]
+    async fn client_serves_api_requests_through_tower_layers() {
+        let server = MockServer::start();
+        let get = server.mock(|when, then| {
+            when.method(GET)
+                .path("/ajax.php")
+                .query_param("f", "check_email")
+                .query_param("seq", "1")
+                .query_param_exists("_")
+                .header("Authorization", "ApiToken test");
+            then.status(200).json_body(json!({ "list": [] }));
+        });
+        let post = server.mock(|when, then| {
+            when.method(POST)
+                .path("/ajax.php")
+                .query_param("f", "forget_me")
+                .x_www_form_urlencoded_tuple("email_addr", "alias@example.com");
+            then.status(200).json_body(json!(true));
+        });
+        let client = Client::new_for_tests(server.base_url(), server.url("/ajax.php"));
+        let service = ServiceBuilder::new().concurrency_limit(1).service(client);
+
+        let response = service
+            .clone()
+            .oneshot(ApiRequest::get("check_email").param("seq", "1"))
+            .await
+            .unwrap();
+        assert_eq!(response, json!({ "list": [] }));
+        let forget = ApiRequest::post("forget_me").param("email_addr", "alias@example.com");
+        assert_eq!(service.oneshot(forget).await.unwrap(), json!(true));
+
+        get.assert();
+        post.assert();
+    }
+}
@@ -0,0 +1,122 @@
+//! Portable snapshot of a GuerrillaMail session.
+
+use crate::Result;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+/// Everything needed to resume a GuerrillaMail session in another process.
+///
+/// Obtain one from [`Client::session`](crate::Client::session) and hand it to
+/// [`ClientBuilder::session`](crate::ClientBuilder::session) to build a client that skips the
+/// homepage bootstrap and keeps talking to the same inbox.
+///
+/// Sessions can be persisted as JSON with [`save`](Session::save) and [`load`](Session::load).
+/// The `Debug` output redacts every field; treat the values as credentials.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Session {
+    /// Raw API token (without the `ApiToken ` prefix).
+    pub api_token: String,
+    /// Latest `sid_token` returned by the API, if any.
+    #[serde(default)]
+    pub sid_token: Option<String>,
+    /// Session cookies in `Cookie` header form (`name=value; name2=value2`).
+    #[serde(default)]
+    pub cookies: String,
+}
+
+impl fmt::Debug for Session {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Session")
+            .field("api_token", &"<redacted>")
+            .field("sid_token", &self.sid_token.as_ref().map(|_| "<redacted>"))
+            .field("cookies", &"<redacted>")
+            .finish()
+    }
+}
+
+impl Session {
+    /// Write the session to `path` as JSON, replacing any existing file.
+    ///
+    /// On Unix the file is created with mode `0600` (and an existing file is reset to it), so
+    /// only the current user can read the credentials. This is a blocking call.
+    ///
+    /// # Errors
+    /// - Returns `Error::Io` if the file cannot be created, written, or re-permissioned.
+    /// - Returns `Error::Json` if serialization fails.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let json = serde_json::to_vec_pretty(self)?;
+
+        let mut options = OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+            options.mode(0o600);
+            let mut file = options.open(path)?;
+            // `mode` only applies on creation; tighten files that already existed too.
+            file.set_permissions(fs::Permissions::from_mode(0o600))?;
+            file.write_all(&json)?;
+        }
+        #[cfg(not(unix))]
+        {
+            let mut file = options.open(path)?;
+            file.write_all(&json)?;
+        }
+        Ok(())
+    }
+
+    /// Read a session previously written by [`save`](Session::save). This is a blocking call.
+    ///
+    /// # Errors
+    /// - Returns `Error::Io` if the file cannot be read.
+    /// - Returns `Error::Json` if the file is not a valid session document.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let raw = fs::read(path)?;
+        Ok(serde_json::from_slice(&raw)?)
+    }
+}
+
+/// Session with a placeholder API token, so a test client skips the homepage bootstrap.
+#[cfg(test)]
+pub(crate) fn test_session() -> Session {
+    Session {
+        api_token: "token".to_string(),
+        sid_token: None,
+        cookies: String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let path = std::env::temp_dir().join(format!(
+            "guerrillamail-session-{}-{}.json",
+            std::process::id(),
+            rand::random::<u32>()
+        ));
+        let session = Session {
+            api_token: "token".to_string(),
+            sid_token: Some("sid".to_string()),
+            cookies: "PHPSESSID=abc".to_string(),
+        };
+
+        session.save(&path).unwrap();
+        let loaded = Session::load(&path).unwrap();
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, session);
+    }
+}
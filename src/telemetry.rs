@@ -0,0 +1,169 @@
+//! Request descriptions for metrics, and `tracing` spans around each HTTP exchange.
+//!
+//! With the `tracing` feature, every request the client sends, including bootstrap, retries, and re-authentication, runs
+//! inside a `guerrillamail.request` span at `DEBUG` level with these fields:
+//!
+//! - `function`: the AJAX function (`check_email`, `set_email_user`, ...), or the URL path for
+//!   page and download requests.
+//! - `alias`: the inbox the request concerns, when it names one.
+//! - `method`: the HTTP method.
+//! - `request_id`: a fresh 16-hex-digit ID, also carried by errors from the exchange (see
+//!   [`Error::request_id`](crate::Error::request_id)) and [`RequestMetrics`](crate::RequestMetrics).
+//! - `status` and `elapsed_ms`: recorded when the exchange completes.
+//!
+//! A `DEBUG` event closes each span, or a `WARN` event when no response was received. Tokens,
+//! cookies, and headers are never recorded.
+//!
+//! [`ClientBuilder::log_requests`](crate::ClientBuilder::log_requests) additionally emits one
+//! `DEBUG` record per exchange on the `guerrillamail::http` target, with the endpoint, the
+//! query and form parameters (token values replaced by `***`), the status, the response body
+//! size when known, and the elapsed time.
+
+#[cfg(feature = "tracing")]
+use crate::Result;
+use crate::middleware::{redact, request_pairs};
+#[cfg(feature = "tracing")]
+use std::future::Future;
+#[cfg(feature = "tracing")]
+use std::time::Duration;
+#[cfg(feature = "tracing")]
+use tracing::{Instrument, field::Empty};
+
+/// Client-generated correlation ID of one HTTP exchange.
+///
+/// Stored in the response's extensions so errors built from the response can carry it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RequestId(pub(crate) String);
+
+impl RequestId {
+    pub(crate) fn generate() -> Self {
+        Self(format!("{:016x}", rand::random::<u64>()))
+    }
+}
+
+/// What a request is for, without anything secret.
+#[derive(Debug, Clone)]
+pub(crate) struct RequestInfo {
+    pub(crate) id: RequestId,
+    pub(crate) function: String,
+    /// Only reported in spans.
+    #[cfg_attr(not(feature = "tracing"), allow(dead_code))]
+    pub(crate) alias: Option<String>,
+    /// Only reported in spans.
+    #[cfg_attr(not(feature = "tracing"), allow(dead_code))]
+    pub(crate) method: reqwest::Method,
+    /// Query and form parameters, token values redacted; only reported in request logs.
+    #[cfg_attr(not(feature = "tracing"), allow(dead_code))]
+    pub(crate) params: Vec<(String, String)>,
+}
+
+impl RequestInfo {
+    pub(crate) fn new(id: RequestId, request: &reqwest::Request) -> Self {
+        let pairs = redact(request_pairs(request));
+        let param = |name: &str| {
+            pairs
+                .iter()
+                .find(|(key, value)| key == name && !value.is_empty())
+                .map(|(_, value)| value.clone())
+        };
+        Self {
+            id,
+            function: param("f").unwrap_or_else(|| request.url().path().to_string()),
+            alias: param("in").or_else(|| param("email_user")),
+            method: request.method().clone(),
+            params: pairs,
+        }
+    }
+
+    /// Emit the `guerrillamail::http` record for this exchange.
+    #[cfg(feature = "tracing")]
+    pub(crate) fn log(&self, response: &Result<reqwest::Response>, elapsed: Duration) {
+        let params = self
+            .params
+            .iter()
+            .map(|(key, value)| format!("{key}={value}"))
+            .collect::<Vec<_>>()
+            .join("&");
+        let elapsed_ms = elapsed.as_millis() as u64;
+        match response {
+            Ok(response) => tracing::debug!(
+                target: "guerrillamail::http",
+                request_id = %self.id.0,
+                endpoint = %self.function,
+                method = %self.method,
+                params = %params,
+                status = response.status().as_u16(),
+                body_size = response.content_length(),
+                elapsed_ms,
+                "exchange",
+            ),
+            Err(err) => tracing::debug!(
+                target: "guerrillamail::http",
+                request_id = %self.id.0,
+                endpoint = %self.function,
+                method = %self.method,
+                params = %params,
+                error = %err,
+                elapsed_ms,
+                "exchange failed",
+            ),
+        }
+    }
+
+    /// Run `exchange` inside a span describing this request.
+    #[cfg(feature = "tracing")]
+    pub(crate) async fn trace<F>(&self, exchange: F) -> Result<reqwest::Response>
+    where
+        F: Future<Output = Result<reqwest::Response>>,
+    {
+        let span = tracing::debug_span!(
+            "guerrillamail.request",
+            request_id = %self.id.0,
+            function = %self.function,
+            alias = self.alias.as_deref(),
+            method = %self.method,
+            status = Empty,
+            elapsed_ms = Empty,
+        );
+        let started = web_time::Instant::now();
+        let response = exchange.instrument(span.clone()).await;
+        let elapsed_ms = started.elapsed().as_millis() as u64;
+        span.record("elapsed_ms", elapsed_ms);
+        let _entered = span.enter();
+        match &response {
+            Ok(response) => {
+                span.record("status", response.status().as_u16());
+                tracing::debug!(status = response.status().as_u16(), elapsed_ms, "response");
+            }
+            Err(err) => tracing::warn!(error = %err, elapsed_ms, "request failed"),
+        }
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describes_requests_without_secrets() {
+        let client = reqwest::Client::new();
+        let request = client
+            .post("https://www.guerrillamail.com/ajax.php?f=set_email_user&sid_token=secret")
+            .form(&[("email_user", "qa"), ("site", "guerrillamail.com")])
+            .build()
+            .unwrap();
+
+        let info = RequestInfo::new(RequestId::generate(), &request);
+
+        assert_eq!(info.id.0.len(), 16);
+        assert_eq!(info.function, "set_email_user");
+        assert_eq!(info.alias.as_deref(), Some("qa"));
+        assert_eq!(info.method, reqwest::Method::POST);
+        assert!(
+            info.params
+                .contains(&("sid_token".to_string(), "***".to_string()))
+        );
+        assert!(!format!("{info:?}").contains("secret"));
+    }
+}
@@ -0,0 +1,589 @@
+//! Local mock and fake of the GuerrillaMail API for integration tests.
+//!
+//! [`FakeGuerrillaMail`] is an in-process fake that keeps inbox state, for end-to-end flows.
+//! [`MockGuerrillaMail`] starts an [`httpmock`] server on `127.0.0.1` that answers the
+//! `ajax.php` calls the client makes with canned, deterministic responses, so code built on
+//! [`Client`] can be tested without network access. Only available with the `testing` cargo
+//! feature; enable it in `[dev-dependencies]`.
+//!
+//! # Example
+//! ```
+//! use guerrillamail_client::testing::{MockGuerrillaMail, MockMessage};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), guerrillamail_client::Error> {
+//! let mock = MockGuerrillaMail::start_with(
+//!     "signup@sharklasers.com",
+//!     vec![MockMessage::new("7", "noreply@example.com", "Verify", "<p>Code: 424242</p>")],
+//! )
+//! .await;
+//! let client = mock.client().await?;
+//!
+//! let email = client.create_email("signup").await?;
+//! let messages = client.get_messages(&email).await?;
+//! let details = client.fetch_email(&email, &messages[0].mail_id).await?;
+//! assert!(details.mail_body.contains("424242"));
+//! assert_eq!(mock.hits("check_email"), 1);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::middleware::request_pairs;
+use crate::transport::{Transport, TransportFuture};
+use crate::{Client, ClientBuilder, Result};
+use httpmock::Method::{GET, POST};
+use httpmock::{Mock, MockServer};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+pub use httpmock;
+
+/// Address handed out by [`MockGuerrillaMail::start`].
+pub const TEST_ADDRESS: &str = "test@sharklasers.com";
+
+/// Timestamp (Unix seconds) reported for the canned address and messages.
+pub const TEST_TIMESTAMP: u64 = 1_700_000_000;
+
+/// A message served by [`MockGuerrillaMail`] from `check_email` and `fetch_email`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockMessage {
+    /// Message ID (`mail_id`).
+    pub id: String,
+    /// Sender address.
+    pub from: String,
+    /// Subject line.
+    pub subject: String,
+    /// HTML body returned by `fetch_email`; the listing excerpt is its first 50 characters.
+    pub body: String,
+}
+
+impl MockMessage {
+    /// A message with the given fields.
+    pub fn new(
+        id: impl Into<String>,
+        from: impl Into<String>,
+        subject: impl Into<String>,
+        body: impl Into<String>,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            from: from.into(),
+            subject: subject.into(),
+            body: body.into(),
+        }
+    }
+}
+
+impl Default for MockMessage {
+    /// A welcome message with ID `"1"`.
+    fn default() -> Self {
+        Self::new(
+            "1",
+            "no-reply@guerrillamail.com",
+            "Welcome to Guerrilla Mail",
+            "<p>Welcome to Guerrilla Mail.</p>",
+        )
+    }
+}
+
+/// A running mock of GuerrillaMail's `ajax.php`, stopped when dropped.
+///
+/// Answers, for any alias:
+/// - `get_email_address` (bootstrap and [`Client::current_address`]) with the address and a
+///   session token,
+/// - `set_email_user` with the address,
+/// - `check_email` with every configured message,
+/// - `fetch_email` with the matching message's details (`404` for unknown IDs),
+/// - `forget_me` with `true`.
+///
+/// Responses never change, so tests see the same inbox however often they poll. For anything
+/// else, add mocks to [`server`](Self::server); requests that match no mock get a `404`.
+pub struct MockGuerrillaMail {
+    server: MockServer,
+    /// Mock IDs of the canned handlers, by AJAX function.
+    mocks: HashMap<&'static str, usize>,
+    /// Mock IDs of the per-message `fetch_email` handlers.
+    fetch_ids: Vec<usize>,
+}
+
+impl std::fmt::Debug for MockGuerrillaMail {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MockGuerrillaMail")
+            .field("base_url", &self.base_url())
+            .finish_non_exhaustive()
+    }
+}
+
+impl MockGuerrillaMail {
+    /// Start a mock serving [`TEST_ADDRESS`] with one [`MockMessage::default`].
+    pub async fn start() -> Self {
+        Self::start_with(TEST_ADDRESS, vec![MockMessage::default()]).await
+    }
+
+    /// Start a mock serving `address` with `messages` in its inbox.
+    pub async fn start_with(address: impl Into<String>, messages: Vec<MockMessage>) -> Self {
+        let address = address.into();
+        let alias = address.split('@').next().unwrap_or_default().to_string();
+        let server = MockServer::start_async().await;
+        let mut mocks = HashMap::new();
+
+        let session = json!({
+            "email_addr": address,
+            "email_timestamp": TEST_TIMESTAMP,
+            "alias": alias,
+            "sid_token": "mock-sid-token",
+        });
+        let mock = server
+            .mock_async(|when, then| {
+                when.method(GET)
+                    .path("/ajax.php")
+                    .query_param("f", "get_email_address");
+                then.status(200).json_body(session);
+            })
+            .await;
+        mocks.insert("get_email_address", mock.id);
+
+        let mock = server
+            .mock_async(|when, then| {
+                when.method(POST)
+                    .path("/ajax.php")
+                    .query_param("f", "set_email_user");
+                then.status(200).json_body(json!({
+                    "email_addr": address,
+                    "email_timestamp": TEST_TIMESTAMP,
+                }));
+            })
+            .await;
+        mocks.insert("set_email_user", mock.id);
+
+        let list: Vec<_> = messages
+            .iter()
+            .map(|message| {
+                json!({
+                    "mail_id": message.id,
+                    "mail_from": message.from,
+                    "mail_subject": message.subject,
+                    "mail_excerpt": message.body.chars().take(50).collect::<String>(),
+                    "mail_timestamp": TEST_TIMESTAMP.to_string(),
+                    "mail_read": 0,
+                })
+            })
+            .collect();
+        let mock = server
+            .mock_async(|when, then| {
+                when.method(GET)
+                    .path("/ajax.php")
+                    .query_param("f", "check_email");
+                then.status(200)
+                    .json_body(json!({ "list": list, "count": list.len() }));
+            })
+            .await;
+        mocks.insert("check_email", mock.id);
+
+        let mut fetch_ids = Vec::new();
+        for message in &messages {
+            let mock = server
+                .mock_async(|when, then| {
+                    when.method(GET)
+                        .path("/ajax.php")
+                        .query_param("f", "fetch_email")
+                        .query_param("email_id", &message.id);
+                    then.status(200).json_body(json!({
+                        "mail_id": message.id,
+                        "mail_from": message.from,
+                        "mail_subject": message.subject,
+                        "mail_body": message.body,
+                        "mail_timestamp": TEST_TIMESTAMP.to_string(),
+                    }));
+                })
+                .await;
+            fetch_ids.push(mock.id);
+        }
+
+        let mock = server
+            .mock_async(|when, then| {
+                when.method(POST)
+                    .path("/ajax.php")
+                    .query_param("f", "forget_me");
+                then.status(200).json_body(json!(true));
+            })
+            .await;
+        mocks.insert("forget_me", mock.id);
+
+        Self {
+            server,
+            mocks,
+            fetch_ids,
+        }
+    }
+
+    /// Root URL of the mock, for [`ClientBuilder::base_url`].
+    pub fn base_url(&self) -> String {
+        self.server.base_url()
+    }
+
+    /// URL of the mock `ajax.php`, for [`ClientBuilder::ajax_url`].
+    pub fn ajax_url(&self) -> String {
+        self.server.url("/ajax.php")
+    }
+
+    /// A builder pointed at this mock, for tests that need further options.
+    pub fn client_builder(&self) -> ClientBuilder {
+        Client::builder()
+            .base_url(self.base_url())
+            .ajax_url(self.ajax_url())
+    }
+
+    /// A client pointed at this mock, bootstrapped against it.
+    ///
+    /// # Errors
+    /// Propagates errors from [`ClientBuilder::build`].
+    pub async fn client(&self) -> Result<Client> {
+        self.client_builder().build().await
+    }
+
+    /// The underlying server, for adding mocks or inspecting requests.
+    pub fn server(&self) -> &MockServer {
+        &self.server
+    }
+
+    /// How many requests the canned handler for the AJAX function `function` has answered.
+    ///
+    /// Counts `fetch_email` calls across all messages; returns `0` for functions without a
+    /// canned handler.
+    pub fn hits(&self, function: &str) -> usize {
+        if function == "fetch_email" {
+            return self
+                .fetch_ids
+                .iter()
+                .map(|&id| Mock::new(id, &self.server).hits())
+                .sum();
+        }
+        self.mocks
+            .get(function)
+            .map_or(0, |&id| Mock::new(id, &self.server).hits())
+    }
+}
+
+/// An in-process fake of GuerrillaMail that keeps inbox state, for end-to-end tests.
+///
+/// Unlike [`MockGuerrillaMail`], which replays fixed responses, the fake behaves like the
+/// service: `set_email_user` creates (or reopens) an inbox, messages injected with
+/// [`deliver`](Self::deliver) show up in `check_email`, `fetch_email` marks them read, and
+/// `del_email` and `forget_me` remove them. That is enough to drive watchers, pools, and cleanup
+/// code without the real service.
+///
+/// The fake is a [`Transport`], so it runs without sockets: build clients with
+/// [`client`](Self::client) or pass a clone to [`ClientBuilder::transport`]. Clones share state,
+/// and several clients may share one fake. Addresses are `alias@site`, where `site` is the
+/// client's [`site`](ClientBuilder::site) or the domain passed to
+/// [`Client::create_email_with_domain`]. Requests the fake does not know get a `404`.
+///
+/// # Example
+/// ```
+/// use guerrillamail_client::testing::FakeGuerrillaMail;
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), guerrillamail_client::Error> {
+/// let fake = FakeGuerrillaMail::new();
+/// let client = fake.client().await?;
+///
+/// let email = client.create_email("signup").await?;
+/// assert!(client.get_messages(&email).await?.is_empty());
+///
+/// fake.deliver(&email, "noreply@example.com", "Verify", "<p>Code: 424242</p>");
+/// let messages = client.get_messages(&email).await?;
+/// assert_eq!(messages[0].mail_subject, "Verify");
+///
+/// client.delete_email(&email).await?;
+/// assert!(fake.addresses().is_empty());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FakeGuerrillaMail {
+    state: Arc<Mutex<FakeState>>,
+}
+
+#[derive(Debug, Default)]
+struct FakeState {
+    /// Inboxes by alias.
+    inboxes: HashMap<String, FakeInbox>,
+    /// Alias of the address the session currently holds.
+    current: Option<String>,
+    next_id: u64,
+}
+
+#[derive(Debug)]
+struct FakeInbox {
+    address: String,
+    messages: Vec<(MockMessage, bool)>,
+}
+
+impl FakeGuerrillaMail {
+    /// An empty fake with no inboxes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A builder whose requests are served by this fake.
+    pub fn client_builder(&self) -> ClientBuilder {
+        Client::builder().transport(self.clone())
+    }
+
+    /// A client whose requests are served by this fake, bootstrapped against it.
+    ///
+    /// # Errors
+    /// Propagates errors from [`ClientBuilder::build`].
+    pub async fn client(&self) -> Result<Client> {
+        self.client_builder().build().await
+    }
+
+    /// Deliver a message to `email`, creating the inbox if needed, and return its ID.
+    pub fn deliver(
+        &self,
+        email: &str,
+        from: impl Into<String>,
+        subject: impl Into<String>,
+        body: impl Into<String>,
+    ) -> String {
+        let mut state = self.lock();
+        state.next_id += 1;
+        let id = state.next_id.to_string();
+        let (alias, _) = email.split_once('@').unwrap_or((email, "sharklasers.com"));
+        let inbox = state.inbox(alias, email);
+        inbox
+            .messages
+            .push((MockMessage::new(&id, from, subject, body), false));
+        id
+    }
+
+    /// Messages currently in the inbox of `email`, oldest first.
+    pub fn messages(&self, email: &str) -> Vec<MockMessage> {
+        let alias = email.split('@').next().unwrap_or_default();
+        self.lock()
+            .inboxes
+            .get(alias)
+            .map(|inbox| {
+                inbox
+                    .messages
+                    .iter()
+                    .map(|(message, _)| message.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Addresses with an open inbox, sorted.
+    pub fn addresses(&self) -> Vec<String> {
+        let mut addresses: Vec<_> = self
+            .lock()
+            .inboxes
+            .values()
+            .map(|inbox| inbox.address.clone())
+            .collect();
+        addresses.sort();
+        addresses
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, FakeState> {
+        self.state.lock().expect("fake state lock poisoned")
+    }
+
+    /// Answer one request from the current state.
+    fn respond(&self, request: &reqwest::Request) -> (u16, Value) {
+        if !request.url().path().ends_with("/ajax.php") {
+            return (404, Value::Null);
+        }
+        let params = request_pairs(request);
+        let param = |name: &str| {
+            params
+                .iter()
+                .find(|(key, _)| key == name)
+                .map_or("", |(_, value)| value.as_str())
+        };
+
+        let mut state = self.lock();
+        let alias = param("in");
+        match param("f") {
+            "get_email_address" => {
+                // A fresh session reports a placeholder address without opening an inbox.
+                let current = state.current.as_deref();
+                match current.and_then(|alias| Some((alias, state.inboxes.get(alias)?))) {
+                    Some((alias, inbox)) => (200, session_json(&inbox.address, alias)),
+                    None => (200, session_json("guest@sharklasers.com", "guest")),
+                }
+            }
+            "set_email_user" => {
+                let alias = param("email_user").to_string();
+                let address = format!("{alias}@{}", param("site"));
+                let address = state.inbox(&alias, &address).address.clone();
+                state.current = Some(alias.clone());
+                (200, session_json(&address, &alias))
+            }
+            "check_email" => {
+                let list: Vec<_> = state
+                    .inboxes
+                    .get(alias)
+                    .into_iter()
+                    .flat_map(|inbox| &inbox.messages)
+                    .map(|(message, read)| {
+                        json!({
+                            "mail_id": message.id,
+                            "mail_from": message.from,
+                            "mail_subject": message.subject,
+                            "mail_excerpt": message.body.chars().take(50).collect::<String>(),
+                            "mail_timestamp": TEST_TIMESTAMP.to_string(),
+                            "mail_read": u8::from(*read),
+                        })
+                    })
+                    .collect();
+                (200, json!({ "list": list, "count": list.len() }))
+            }
+            "fetch_email" => {
+                let id = param("email_id");
+                let message = state
+                    .inboxes
+                    .get_mut(alias)
+                    .and_then(|inbox| inbox.messages.iter_mut().find(|(m, _)| m.id == id));
+                match message {
+                    Some((message, read)) => {
+                        *read = true;
+                        let details = json!({
+                            "mail_id": message.id,
+                            "mail_from": message.from,
+                            "mail_subject": message.subject,
+                            "mail_body": message.body,
+                            "mail_timestamp": TEST_TIMESTAMP.to_string(),
+                        });
+                        (200, details)
+                    }
+                    None => (404, Value::Null),
+                }
+            }
+            "del_email" => {
+                let ids: Vec<&str> = params
+                    .iter()
+                    .filter(|(key, _)| key == "email_ids[]")
+                    .map(|(_, value)| value.as_str())
+                    .collect();
+                let mut deleted = Vec::new();
+                if let Some(inbox) = state.inboxes.get_mut(alias) {
+                    inbox.messages.retain(|(message, _)| {
+                        let delete = ids.contains(&message.id.as_str());
+                        if delete {
+                            deleted.push(message.id.clone());
+                        }
+                        !delete
+                    });
+                }
+                (200, json!({ "deleted_ids": deleted }))
+            }
+            "forget_me" => {
+                state.inboxes.remove(alias);
+                if state.current.as_deref() == Some(alias) {
+                    state.current = None;
+                }
+                (200, json!(true))
+            }
+            "extend" if state.inboxes.contains_key(alias) => (
+                200,
+                json!({ "email_timestamp": TEST_TIMESTAMP, "expired": false }),
+            ),
+            _ => (404, Value::Null),
+        }
+    }
+}
+
+impl FakeState {
+    /// The inbox for `alias`, opened at `address` if it does not exist yet.
+    fn inbox(&mut self, alias: &str, address: &str) -> &mut FakeInbox {
+        self.inboxes
+            .entry(alias.to_string())
+            .or_insert_with(|| FakeInbox {
+                address: address.to_string(),
+                messages: Vec::new(),
+            })
+    }
+}
+
+impl Transport for FakeGuerrillaMail {
+    fn send(&self, request: reqwest::Request) -> TransportFuture<'_> {
+        let (status, body) = self.respond(&request);
+        Box::pin(async move {
+            let response = http::Response::builder()
+                .status(status)
+                .header("content-type", "application/json")
+                .body(body.to_string())
+                .expect("static response parts are valid");
+            Ok(response.into())
+        })
+    }
+}
+
+fn session_json(address: &str, alias: &str) -> Value {
+    json!({
+        "email_addr": address,
+        "email_timestamp": TEST_TIMESTAMP,
+        "alias": alias,
+        "sid_token": "fake-sid-token",
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn serves_a_canned_inbox() {
+        let mock = MockGuerrillaMail::start().await;
+        let client = mock.client().await.unwrap();
+
+        let email = client.create_email("anything").await.unwrap();
+        let messages = client.get_messages(&email).await.unwrap();
+        let details = client
+            .fetch_email(&email, &messages[0].mail_id)
+            .await
+            .unwrap();
+        let missing = client.fetch_email(&email, "404").await;
+
+        assert_eq!(email, TEST_ADDRESS);
+        assert_eq!(messages.len(), 1);
+        assert_eq!(details.mail_subject, "Welcome to Guerrilla Mail");
+        assert!(missing.is_err());
+        assert!(client.delete_email(&email).await.unwrap());
+        assert_eq!(mock.hits("get_email_address"), 1);
+        assert_eq!(mock.hits("fetch_email"), 1);
+        assert_eq!(mock.hits("forget_me"), 1);
+    }
+
+    #[tokio::test]
+    async fn fake_tracks_inbox_state_across_calls() {
+        let fake = FakeGuerrillaMail::new();
+        let client = fake.client().await.unwrap();
+
+        let email = client.create_email("flow").await.unwrap();
+        assert!(email.is_exact());
+        assert_eq!(email, "flow@guerrillamail.com");
+        let first = fake.deliver(&email, "a@example.com", "One", "<p>1</p>");
+        let second = fake.deliver(&email, "b@example.com", "Two", "<p>2</p>");
+
+        let details = client.fetch_email(&email, &first).await.unwrap();
+        assert_eq!(details.mail_subject, "One");
+        let messages = client.get_messages(&email).await.unwrap();
+        assert_eq!(messages.len(), 2);
+        assert!(messages[0].mail_read && !messages[1].mail_read);
+
+        let deleted = client
+            .delete_messages(&email, &[first.as_str()])
+            .await
+            .unwrap();
+        assert_eq!(deleted, [first]);
+        assert_eq!(fake.messages(&email)[0].id, second);
+        assert_eq!(fake.addresses(), [email.to_string()]);
+
+        assert!(client.delete_email(&email).await.unwrap());
+        assert!(fake.addresses().is_empty());
+    }
+}
@@ -0,0 +1,250 @@
+//! Pluggable HTTP transport.
+
+use crate::Result;
+#[cfg(target_arch = "wasm32")]
+use crate::jar::{CookieStore, Jar};
+#[cfg(not(target_arch = "wasm32"))]
+use reqwest::cookie::{CookieStore, Jar};
+use reqwest::header::{COOKIE, SET_COOKIE};
+#[cfg(not(target_arch = "wasm32"))]
+use reqwest::{ResponseBuilderExt, Url};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+#[cfg(target_arch = "wasm32")]
+use std::time::Duration;
+
+/// Future returned by [`Transport::send`].
+#[cfg(not(target_arch = "wasm32"))]
+pub type TransportFuture<'a> = Pin<Box<dyn Future<Output = Result<reqwest::Response>> + Send + 'a>>;
+
+/// Future returned by [`Transport::send`]; not `Send` on wasm32, where fetch futures are not.
+#[cfg(target_arch = "wasm32")]
+pub type TransportFuture<'a> = Pin<Box<dyn Future<Output = Result<reqwest::Response>> + 'a>>;
+
+/// Sends prepared requests on behalf of a [`Client`], for [`ClientBuilder::transport`].
+///
+/// The client still builds each `reqwest::Request` (URL, query, form body, and headers) and
+/// interprets the response; a transport only moves it over the wire. Implement this to serve
+/// canned responses in offline tests, add your own retries, or run over something other than
+/// reqwest's connector. `reqwest::Client` implements it and is the default.
+///
+/// Responses can be built from an `http::Response` with `reqwest::Response::from`. Set the URL
+/// that answered with [`reqwest::ResponseBuilderExt::url`] when it differs from the request's
+/// (after following redirects, say); otherwise the request URL is reported.
+///
+/// [`Client`]: crate::Client
+/// [`ClientBuilder::transport`]: crate::ClientBuilder::transport
+///
+/// # Example
+/// ```
+/// use guerrillamail_client::{Transport, TransportFuture};
+///
+/// /// Answers every request with an empty inbox.
+/// struct EmptyInbox;
+///
+/// impl Transport for EmptyInbox {
+///     fn send(&self, _request: reqwest::Request) -> TransportFuture<'_> {
+///         Box::pin(async {
+///             let response = http::Response::new(r#"{"list":[]}"#);
+///             Ok(reqwest::Response::from(response))
+///         })
+///     }
+/// }
+/// ```
+pub trait Transport: Send + Sync {
+    /// Send `request` and return the response, whatever its status.
+    ///
+    /// Return `Error::Request` (or another error) only when no response was received.
+    fn send(&self, request: reqwest::Request) -> TransportFuture<'_>;
+}
+
+impl Transport for reqwest::Client {
+    fn send(&self, request: reqwest::Request) -> TransportFuture<'_> {
+        Box::pin(async move { Ok(self.execute(request).await?) })
+    }
+}
+
+/// Read `response` in full, for callers that need the body and still hand a response on.
+///
+/// Convert the result back with `reqwest::Response::from`; the URL and extensions (such as the
+/// request ID) carry over.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) async fn buffer(response: reqwest::Response) -> Result<http::Response<Vec<u8>>> {
+    let status = response.status();
+    let version = response.version();
+    let headers = response.headers().clone();
+    let extensions = response.extensions().clone();
+    let url = response.url().clone();
+    let body = response.bytes().await?;
+
+    let mut buffered = http::Response::builder()
+        .url(url)
+        .body(body.to_vec())
+        .expect("empty response parts are valid");
+    *buffered.status_mut() = status;
+    *buffered.version_mut() = version;
+    *buffered.headers_mut() = headers;
+    buffered.extensions_mut().extend(extensions);
+    Ok(buffered)
+}
+
+/// Host reqwest reports for a response built from an `http::Response` without a URL.
+#[cfg(not(target_arch = "wasm32"))]
+const NO_URL_HOST: &str = "no.url.provided.local";
+
+/// `response`, reporting `url` if its transport did not say where it came from.
+#[cfg(not(target_arch = "wasm32"))]
+fn with_url(response: reqwest::Response, url: Url) -> reqwest::Response {
+    if response.url().host_str() != Some(NO_URL_HOST) {
+        return response;
+    }
+    let (mut parts, body) = http::Response::<reqwest::Body>::from(response).into_parts();
+    let located = http::Response::builder()
+        .url(url)
+        .body(())
+        .expect("empty response is valid");
+    parts.extensions.extend(located.into_parts().0.extensions);
+    http::Response::from_parts(parts, body).into()
+}
+
+/// reqwest's fetch backend with the client's timeout applied to each request, since its
+/// `ClientBuilder` has no timeout of its own.
+#[cfg(target_arch = "wasm32")]
+pub(crate) struct Fetch {
+    http: reqwest::Client,
+    timeout: Duration,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Fetch {
+    pub(crate) fn new(http: reqwest::Client, timeout: Duration) -> Self {
+        Self { http, timeout }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Transport for Fetch {
+    fn send(&self, mut request: reqwest::Request) -> TransportFuture<'_> {
+        request.timeout_mut().get_or_insert(self.timeout);
+        self.http.send(request)
+    }
+}
+
+/// Custom transport with the client's cookie jar applied, as reqwest does for its own client.
+pub(crate) struct CookieTransport {
+    inner: Arc<dyn Transport>,
+    cookies: Arc<Jar>,
+}
+
+impl CookieTransport {
+    pub(crate) fn new(inner: Arc<dyn Transport>, cookies: Arc<Jar>) -> Self {
+        Self { inner, cookies }
+    }
+}
+
+impl Transport for CookieTransport {
+    fn send(&self, mut request: reqwest::Request) -> TransportFuture<'_> {
+        Box::pin(async move {
+            let url = request.url().clone();
+            if let Some(cookie) = self.cookies.cookies(&url) {
+                request.headers_mut().entry(COOKIE).or_insert(cookie);
+            }
+            let response = self.inner.send(request).await?;
+            #[cfg(not(target_arch = "wasm32"))]
+            let response = with_url(response, url.clone());
+            let mut set_cookies = response.headers().get_all(SET_COOKIE).iter().peekable();
+            if set_cookies.peek().is_some() {
+                self.cookies.set_cookies(&mut set_cookies, &url);
+            }
+            Ok(response)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Client;
+    use std::sync::Mutex;
+
+    /// Answers from a fixed body per AJAX function and records what was sent.
+    #[derive(Default)]
+    struct Canned {
+        sent: Mutex<Vec<reqwest::Request>>,
+    }
+
+    impl Transport for Arc<Canned> {
+        fn send(&self, request: reqwest::Request) -> TransportFuture<'_> {
+            let function = request
+                .url()
+                .query_pairs()
+                .find(|(key, _)| key == "f")
+                .map(|(_, value)| value.into_owned());
+            self.sent.lock().unwrap().push(request);
+            Box::pin(async move {
+                let response = match function.as_deref() {
+                    Some("get_email_address") => http::Response::builder()
+                        .header("set-cookie", "PHPSESSID=canned; path=/")
+                        .body(r#"{"sid_token":"offline"}"#),
+                    Some("check_email") => http::Response::builder().body(r#"{"list":[]}"#),
+                    None => http::Response::builder().body(
+                        r#"<select name="gm_host"><option value="offline.test">offline.test</option></select>"#,
+                    ),
+                    _ => http::Response::builder().status(404).body(""),
+                };
+                Ok(reqwest::Response::from(response.unwrap()))
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn custom_transport_serves_bootstrap_and_api_calls_offline() {
+        let transport = Arc::new(Canned::default());
+
+        let client = Client::builder()
+            .transport(Arc::clone(&transport))
+            .build()
+            .await
+            .unwrap();
+        let messages = client.get_messages("alias@example.com").await.unwrap();
+
+        assert!(messages.is_empty());
+        let sent = transport.sent.lock().unwrap();
+        let api_call = sent.last().unwrap();
+        assert_eq!(api_call.headers()["authorization"], "ApiToken offline");
+        assert_eq!(api_call.headers()["cookie"], "PHPSESSID=canned");
+        assert_eq!(client.session().cookies, "PHPSESSID=canned");
+    }
+
+    #[tokio::test]
+    async fn fetch_domains_goes_through_custom_transport() {
+        let transport = Arc::new(Canned::default());
+        let client = Client::builder()
+            .transport(Arc::clone(&transport))
+            .build()
+            .await
+            .unwrap();
+
+        let domains = client.fetch_domains().await.unwrap();
+
+        assert_eq!(domains, ["offline.test"]);
+        let sent = transport.sent.lock().unwrap();
+        let page = sent.last().unwrap();
+        assert_eq!(page.url().path(), "/");
+        assert!(page.headers().contains_key("user-agent"));
+        assert_eq!(page.headers()["cookie"], "PHPSESSID=canned");
+    }
+
+    #[tokio::test]
+    async fn custom_transport_responses_report_the_request_url() {
+        let transport = CookieTransport::new(Arc::new(Arc::new(Canned::default())), Arc::default());
+        let url = "https://www.guerrillamail.com/ajax.php?f=check_email";
+        let request = reqwest::Request::new(reqwest::Method::GET, url.parse().unwrap());
+
+        let response = transport.send(request).await.unwrap();
+
+        assert_eq!(response.url().as_str(), url);
+        assert_eq!(response.text().await.unwrap(), r#"{"list":[]}"#);
+    }
+}
@@ -0,0 +1,371 @@
+//! Concurrent polling of many inboxes multiplexed onto a single event channel.
+
+use crate::{CancellationToken, Client, Error, Message};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{Semaphore, mpsc};
+use tokio::task::{JoinHandle, JoinSet};
+
+/// Event produced by a [`MultiWatcher`].
+#[derive(Debug)]
+pub enum WatchEvent {
+    /// A message was seen for the first time in a watched inbox.
+    Message {
+        /// Address whose inbox contained the message.
+        address: String,
+        /// Message header as returned by [`Client::get_messages`].
+        message: Message,
+    },
+    /// Polling an inbox failed; the watcher keeps polling it on the next round.
+    PollFailed {
+        /// Address whose poll failed.
+        address: String,
+        /// Error returned by [`Client::get_messages`].
+        error: Error,
+    },
+}
+
+/// Polls many inboxes concurrently and reports new messages on one stream.
+///
+/// Addresses can be added and removed at any time with [`watch`](MultiWatcher::watch) and
+/// [`unwatch`](MultiWatcher::unwatch), including after [`start`](MultiWatcher::start). Each round
+/// polls every watched address via [`Client::get_messages`], with at most
+/// [`concurrency`](MultiWatcher::concurrency) requests in flight, then sleeps for the
+/// [`poll_interval`](MultiWatcher::poll_interval). Each message id is reported once per address.
+///
+/// Only ids still in an inbox's latest listing are remembered, and an address's ids are
+/// forgotten once it is unwatched, so memory stays proportional to the watched inboxes.
+///
+/// `Clone` is cheap; clones share the set of watched addresses.
+///
+/// # Example
+/// ```no_run
+/// # use guerrillamail_client::{Client, MultiWatcher, WatchEvent};
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), guerrillamail_client::Error> {
+/// let client = Client::new().await?;
+/// let watcher = MultiWatcher::new(client.clone()).concurrency(8);
+/// for i in 0..50 {
+///     watcher.watch(client.create_email(&format!("signup{i}")).await?);
+/// }
+///
+/// let mut events = watcher.start();
+/// while let Some(event) = events.next().await {
+///     if let WatchEvent::Message { address, message } = event {
+///         println!("{address}: {}", message.mail_subject);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct MultiWatcher {
+    client: Client,
+    addresses: Arc<Mutex<BTreeSet<String>>>,
+    poll_interval: Duration,
+    concurrency: usize,
+    buffer: usize,
+    cancel: CancellationToken,
+}
+
+impl MultiWatcher {
+    /// Create a watcher with no addresses, polling every 5 seconds with up to 4 concurrent
+    /// requests.
+    pub fn new(client: Client) -> Self {
+        Self {
+            client,
+            addresses: Arc::default(),
+            poll_interval: Duration::from_secs(5),
+            concurrency: 4,
+            buffer: 64,
+            cancel: CancellationToken::new(),
+        }
+    }
+
+    /// Set the pause between polling rounds (default: 5 seconds).
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Set the maximum number of inbox polls in flight at once (default: 4).
+    ///
+    /// # Panics
+    /// Panics if `limit` is zero.
+    pub fn concurrency(mut self, limit: usize) -> Self {
+        assert!(limit > 0, "watcher concurrency must be non-zero");
+        self.concurrency = limit;
+        self
+    }
+
+    /// Set the capacity of the event channel (default: 64).
+    ///
+    /// Polling pauses while the channel is full, so a slow consumer applies back-pressure.
+    ///
+    /// # Panics
+    /// Panics if `capacity` is zero.
+    pub fn buffer(mut self, capacity: usize) -> Self {
+        assert!(capacity > 0, "watcher buffer must be non-zero");
+        self.buffer = capacity;
+        self
+    }
+
+    /// Stop polling when `token` is cancelled.
+    ///
+    /// In-flight polls are abandoned and the [`WatchStream`] ends (`next` returns `None`) once
+    /// buffered events have been drained.
+    pub fn cancel_on(mut self, token: CancellationToken) -> Self {
+        self.cancel = token;
+        self
+    }
+
+    /// Start polling `address`. Returns `false` if it was already watched.
+    pub fn watch(&self, address: impl Into<String>) -> bool {
+        self.addresses
+            .lock()
+            .expect("watcher address set poisoned")
+            .insert(address.into())
+    }
+
+    /// Stop polling `address`. Returns `false` if it was not watched.
+    pub fn unwatch(&self, address: &str) -> bool {
+        self.addresses
+            .lock()
+            .expect("watcher address set poisoned")
+            .remove(address)
+    }
+
+    /// Addresses currently being watched.
+    pub fn addresses(&self) -> Vec<String> {
+        self.addresses
+            .lock()
+            .expect("watcher address set poisoned")
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Spawn the polling task and return the stream of events.
+    ///
+    /// The task runs on the current Tokio runtime until the returned [`WatchStream`] is dropped
+    /// or the [`cancel_on`](MultiWatcher::cancel_on) token is cancelled.
+    pub fn start(&self) -> WatchStream {
+        let (tx, rx) = mpsc::channel(self.buffer);
+        let cancel = self.cancel.clone();
+        let run = self.clone().run(tx);
+        let task = tokio::spawn(async move {
+            tokio::select! {
+                _ = run => {}
+                _ = cancel.cancelled() => {}
+            }
+        });
+        WatchStream { rx, task }
+    }
+
+    async fn run(self, tx: mpsc::Sender<WatchEvent>) {
+        let limit = Arc::new(Semaphore::new(self.concurrency));
+        let seen: Arc<Mutex<HashMap<String, HashSet<String>>>> = Arc::default();
+
+        while !tx.is_closed() {
+            let addresses = self.addresses();
+            seen.lock()
+                .expect("watcher seen set poisoned")
+                .retain(|address, _| addresses.contains(address));
+
+            let mut round = JoinSet::new();
+            for address in addresses {
+                let client = self.client.clone();
+                let limit = Arc::clone(&limit);
+                let seen = Arc::clone(&seen);
+                let tx = tx.clone();
+                round.spawn(async move {
+                    let _permit = limit.acquire_owned().await.expect("semaphore never closed");
+                    let events = match client.get_messages(&address).await {
+                        Ok(messages) => {
+                            let mut seen = seen.lock().expect("watcher seen set poisoned");
+                            let known = seen.remove(&address).unwrap_or_default();
+                            // Ids that left the listing are dropped; expired mail never returns.
+                            let mut listed = HashSet::new();
+                            let events = messages
+                                .into_iter()
+                                .filter(|m| {
+                                    listed.insert(m.mail_id.clone()) && !known.contains(&m.mail_id)
+                                })
+                                .map(|message| WatchEvent::Message {
+                                    address: address.clone(),
+                                    message,
+                                })
+                                .collect();
+                            seen.insert(address.clone(), listed);
+                            events
+                        }
+                        Err(error) => vec![WatchEvent::PollFailed { address, error }],
+                    };
+                    for event in events {
+                        if tx.send(event).await.is_err() {
+                            return;
+                        }
+                    }
+                });
+            }
+            while round.join_next().await.is_some() {}
+
+            tokio::time::sleep(self.poll_interval).await;
+        }
+    }
+}
+
+/// Stream of [`WatchEvent`]s returned by [`MultiWatcher::start`].
+///
+/// Dropping the stream stops the polling task.
+#[derive(Debug)]
+pub struct WatchStream {
+    rx: mpsc::Receiver<WatchEvent>,
+    task: JoinHandle<()>,
+}
+
+impl WatchStream {
+    /// Wait for the next event. Returns `None` only if the polling task has stopped.
+    pub async fn next(&mut self) -> Option<WatchEvent> {
+        self.rx.recv().await
+    }
+}
+
+impl Drop for WatchStream {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::Method::GET;
+    use httpmock::MockServer;
+    use serde_json::json;
+
+    fn message(id: &str) -> serde_json::Value {
+        json!({
+            "mail_id": id,
+            "mail_from": "sender@example.com",
+            "mail_subject": format!("Subject {id}"),
+            "mail_excerpt": "",
+            "mail_timestamp": "1700000000"
+        })
+    }
+
+    #[tokio::test]
+    async fn start_multiplexes_new_messages_from_all_inboxes() {
+        let server = MockServer::start();
+        let base_url = server.base_url();
+
+        let first_mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/ajax.php")
+                .query_param("f", "check_email")
+                .query_param("in", "first");
+            then.status(200)
+                .json_body(json!({ "list": [message("1")] }));
+        });
+        let second_mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/ajax.php")
+                .query_param("f", "check_email")
+                .query_param("in", "second");
+            then.status(200)
+                .json_body(json!({ "list": [message("2")] }));
+        });
+
+        let client = Client::new_for_tests(base_url.clone(), format!("{base_url}/ajax.php"));
+        let watcher = MultiWatcher::new(client)
+            .poll_interval(Duration::from_millis(10))
+            .concurrency(1);
+        assert!(watcher.watch("first@example.com"));
+        assert!(watcher.watch("second@example.com"));
+        assert!(!watcher.watch("first@example.com"));
+
+        let mut events = watcher.start();
+        let mut received = Vec::new();
+        for _ in 0..2 {
+            match events.next().await.unwrap() {
+                WatchEvent::Message { address, message } => {
+                    received.push((address, message.mail_id))
+                }
+                WatchEvent::PollFailed { error, .. } => panic!("unexpected error: {error}"),
+            }
+        }
+        received.sort();
+
+        assert_eq!(
+            received,
+            [
+                ("first@example.com".to_string(), "1".to_string()),
+                ("second@example.com".to_string(), "2".to_string()),
+            ]
+        );
+
+        // Later rounds must not repeat already-seen messages.
+        let repeat = tokio::time::timeout(Duration::from_millis(100), events.next()).await;
+        assert!(repeat.is_err());
+        assert!(first_mock.hits() >= 2);
+        assert!(second_mock.hits() >= 2);
+    }
+
+    #[tokio::test]
+    async fn unwatching_forgets_seen_messages() {
+        let server = MockServer::start();
+        let base_url = server.base_url();
+
+        server.mock(|when, then| {
+            when.method(GET)
+                .path("/ajax.php")
+                .query_param("f", "check_email");
+            then.status(200)
+                .json_body(json!({ "list": [message("1")] }));
+        });
+
+        let client = Client::new_for_tests(base_url.clone(), format!("{base_url}/ajax.php"));
+        let watcher = MultiWatcher::new(client).poll_interval(Duration::from_millis(10));
+        watcher.watch("alias@example.com");
+
+        let mut events = watcher.start();
+        assert!(matches!(
+            events.next().await,
+            Some(WatchEvent::Message { .. })
+        ));
+
+        watcher.unwatch("alias@example.com");
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        watcher.watch("alias@example.com");
+        let again = tokio::time::timeout(Duration::from_secs(1), events.next()).await;
+
+        assert!(matches!(again, Ok(Some(WatchEvent::Message { .. }))));
+    }
+
+    #[tokio::test]
+    async fn cancellation_ends_the_stream() {
+        let server = MockServer::start();
+        let base_url = server.base_url();
+
+        server.mock(|when, then| {
+            when.method(GET)
+                .path("/ajax.php")
+                .query_param("f", "check_email");
+            then.status(200).json_body(json!({ "list": [] }));
+        });
+
+        let client = Client::new_for_tests(base_url.clone(), format!("{base_url}/ajax.php"));
+        let token = CancellationToken::new();
+        let watcher = MultiWatcher::new(client)
+            .poll_interval(Duration::from_millis(10))
+            .cancel_on(token.clone());
+        watcher.watch("alias@example.com");
+
+        let mut events = watcher.start();
+        token.cancel();
+        let end = tokio::time::timeout(Duration::from_secs(1), events.next()).await;
+
+        assert!(matches!(end, Ok(None)));
+    }
+}